@@ -0,0 +1,142 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+use crate::solution::Answer;
+
+
+/// Known-good answers for the real puzzle inputs, keyed by day and then part.
+///
+/// These are stored in a TOML file with one table per day, e.g.
+/// ```toml
+/// [24]
+/// 1 = 99919765949498
+/// 2 = "24913111616151"
+/// ```
+/// Multi-line answers (such as the Day 13 paper) can be written as multi-line strings.
+pub struct Answers {
+    answers: BTreeMap<u32, BTreeMap<u32, String>>,
+}
+
+impl Answers {
+    pub fn load(path: &Path) -> anyhow::Result<Self> {
+        let s = fs::read_to_string(path)
+            .map_err(|e| anyhow::anyhow!("Could not read answers file {}: {}", path.display(), e))?;
+        Self::parse(&s).map_err(|e| anyhow::anyhow!("Could not parse answers file {}: {}", path.display(), e))
+    }
+
+    pub fn parse(s: &str) -> anyhow::Result<Self> {
+        let table: BTreeMap<String, BTreeMap<String, toml::Value>> = toml::from_str(s)?;
+        let mut answers = BTreeMap::new();
+        for (day, parts) in table {
+            let day_number = day.parse::<u32>().map_err(|_| anyhow::anyhow!("Day '{}' is not a number", day))?;
+            let mut day_answers = BTreeMap::new();
+            for (part, value) in parts {
+                let part_number = part.parse::<u32>().map_err(|_| anyhow::anyhow!("Part '{}' of day {} is not a number", part, day))?;
+                let answer = match value {
+                    toml::Value::Integer(i) => i.to_string(),
+                    toml::Value::String(s) => s.trim_end_matches('\n').to_string(),
+                    other => return Err(anyhow::anyhow!("Answer for day {} part {} must be an integer or a string, found {}", day, part, other.type_str())),
+                };
+                day_answers.insert(part_number, answer);
+            }
+            answers.insert(day_number, day_answers);
+        }
+        Ok(Self { answers })
+    }
+
+    pub fn get(&self, day: u32, part: u32) -> Option<&str> {
+        self.answers.get(&day)?.get(&part).map(String::as_str)
+    }
+}
+
+
+/// The outcome of checking one part against its recorded answer.
+#[derive(Debug, PartialEq)]
+pub enum Verdict {
+    Pass,
+    Fail { expected: String, actual: String },
+    /// There is no recorded answer to check against.
+    Missing { actual: String },
+    Error(String),
+}
+
+pub fn check(expected: Option<&str>, actual: &anyhow::Result<Answer>) -> Verdict {
+    let actual = match actual {
+        Ok(answer) => answer.to_string(),
+        Err(e) => return Verdict::Error(e.to_string()),
+    };
+    match expected {
+        Some(expected) if expected == actual => Verdict::Pass,
+        Some(expected) => Verdict::Fail { expected: expected.to_string(), actual },
+        None => Verdict::Missing { actual },
+    }
+}
+
+/// Compares `expected` with `actual` line by line, marking lines only in the expected answer with
+/// `-` and lines only in the actual answer with `+`.
+pub fn diff(expected: &str, actual: &str) -> Vec<String> {
+    let expected_lines = expected.lines().collect::<Vec<&str>>();
+    let actual_lines = actual.lines().collect::<Vec<&str>>();
+    let mut diff_lines = vec![];
+    for i in 0..expected_lines.len().max(actual_lines.len()) {
+        match (expected_lines.get(i), actual_lines.get(i)) {
+            (Some(e), Some(a)) if e == a => diff_lines.push(format!("  {}", e)),
+            (e, a) => {
+                if let Some(e) = e {
+                    diff_lines.push(format!("- {}", e));
+                }
+                if let Some(a) = a {
+                    diff_lines.push(format!("+ {}", a));
+                }
+            },
+        }
+    }
+    diff_lines
+}
+
+
+#[cfg(test)]
+mod tests {
+    use crate::answers::*;
+
+    const TEST_ANSWERS: &str = r#"
+[1]
+1 = 7
+2 = "5"
+
+[13]
+2 = '''
+#####
+#...#
+#####
+'''
+"#;
+
+    #[test]
+    fn test_parse() {
+        let answers = Answers::parse(TEST_ANSWERS).unwrap();
+        assert_eq!(answers.get(1, 1), Some("7"));
+        assert_eq!(answers.get(1, 2), Some("5"));
+        assert_eq!(answers.get(13, 2), Some("#####\n#...#\n#####"));
+        assert_eq!(answers.get(13, 1), None);
+        assert_eq!(answers.get(2, 1), None);
+        assert!(Answers::parse("[one]\n1 = 7").is_err());
+        assert!(Answers::parse("[1]\n1 = 7.5").is_err());
+    }
+
+    #[test]
+    fn test_check() {
+        assert_eq!(check(Some("7"), &Ok(Answer::Int(7))), Verdict::Pass);
+        assert_eq!(check(Some("7"), &Ok(Answer::Int(8))), Verdict::Fail { expected: "7".to_string(), actual: "8".to_string() });
+        assert_eq!(check(None, &Ok(Answer::Int(8))), Verdict::Missing { actual: "8".to_string() });
+        assert_eq!(check(Some("7"), &Err(anyhow::anyhow!("failed"))), Verdict::Error("failed".to_string()));
+        let art = Answer::Art(vec!["#####".to_string(), "#...#".to_string(), "#####".to_string()]);
+        assert_eq!(check(Some("#####\n#...#\n#####"), &Ok(art)), Verdict::Pass);
+    }
+
+    #[test]
+    fn test_diff() {
+        assert_eq!(diff("7", "8"), ["- 7", "+ 8"]);
+        assert_eq!(diff("##\n..\n##", "##\n.#"), ["  ##", "- ..", "+ .#", "- ##"]);
+    }
+}
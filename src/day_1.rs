@@ -1,3 +1,6 @@
+use crate::input::PuzzleInput;
+use crate::registry::Example;
+use crate::solution::{Answer, Solution};
 use crate::utils::parse_ints_to_vec;
 
 
@@ -6,7 +9,7 @@ use crate::utils::parse_ints_to_vec;
 ///
 /// Counts the number of times an integer b is higher than the integer a that immediately
 /// precedes it inside the given vector v.
-pub fn count_increased_measurements(v: &Vec<u64>) -> u64 {
+pub fn count_increased_measurements(v: &[u64]) -> u64 {
     if v.len() <= 1 {
         return 0;
     }
@@ -18,7 +21,7 @@ pub fn count_increased_measurements(v: &Vec<u64>) -> u64 {
         }
     }
 
-    return count;
+    count
 }
 
 /// Day 1: Sonar Sweep (Part 2)
@@ -26,7 +29,7 @@ pub fn count_increased_measurements(v: &Vec<u64>) -> u64 {
 ///
 /// Similar to part 1, but now instead of the individual integers in v, count from
 /// the 3 measurement sliding window.
-pub fn count_3_measurement_sum_increased(v: &Vec<u64>) -> u64 {
+pub fn count_3_measurement_sum_increased(v: &[u64]) -> u64 {
     if v.len() <= 3 {
         return 0;
     }
@@ -38,19 +41,51 @@ pub fn count_3_measurement_sum_increased(v: &Vec<u64>) -> u64 {
         }
     }
 
-    return count;
+    count
 }
 
-pub fn part_1(lines: &Vec<String>) {
-    let v = parse_ints_to_vec(lines);
-    let count = count_increased_measurements(&v);
-    println!("Count: {}", count);
+/// Day 1: Sonar Sweep
+/// https://adventofcode.com/2021/day/1
+pub struct Day1;
+
+impl Solution for Day1 {
+    type Input = Vec<u64>;
+
+    fn parse(input: &PuzzleInput) -> anyhow::Result<Self::Input> {
+        parse_ints_to_vec(input.lines())
+    }
+
+    fn part_1(depths: &Self::Input) -> anyhow::Result<Answer> {
+        Ok(count_increased_measurements(depths).into())
+    }
+
+    fn part_2(depths: &Self::Input) -> anyhow::Result<Answer> {
+        Ok(count_3_measurement_sum_increased(depths).into())
+    }
 }
 
-pub fn part_2(lines: &Vec<String>) {
-    let v = parse_ints_to_vec(lines);
-    let count = count_3_measurement_sum_increased(&v);
-    println!("Count: {}", count);
+register_day! {
+    day: 1,
+    title: "Sonar Sweep",
+    solution: Day1,
+    parts: [1, 2],
+    examples: [
+        Example {
+            input: r#"199
+200
+208
+210
+200
+207
+240
+269
+260
+263
+"#,
+            part_1: Some("7"),
+            part_2: Some("5"),
+        },
+    ],
 }
 
 
@@ -60,18 +95,18 @@ mod tests {
 
     #[test]
     fn test_count_part1() {
-        assert_eq!(count_increased_measurements(&vec![]), 0);
-        assert_eq!(count_increased_measurements(&vec![1]), 0);
-        assert_eq!(count_increased_measurements(&vec![1, 2, 3]), 2);
-        assert_eq!(count_increased_measurements(&vec![199, 200, 208, 210, 200, 207, 240, 269, 260, 263]), 7);
+        assert_eq!(count_increased_measurements(&[]), 0);
+        assert_eq!(count_increased_measurements(&[1]), 0);
+        assert_eq!(count_increased_measurements(&[1, 2, 3]), 2);
+        assert_eq!(count_increased_measurements(&[199, 200, 208, 210, 200, 207, 240, 269, 260, 263]), 7);
     }
 
     #[test]
     fn test_count_part2() {
-        assert_eq!(count_3_measurement_sum_increased(&vec![]), 0);
-        assert_eq!(count_3_measurement_sum_increased(&vec![1]), 0);
-        assert_eq!(count_3_measurement_sum_increased(&vec![1, 2, 3]), 0);
-        assert_eq!(count_3_measurement_sum_increased(&vec![1, 2, 3, 4]), 1);
-        assert_eq!(count_3_measurement_sum_increased(&vec![199, 200, 208, 210, 200, 207, 240, 269, 260, 263]), 5);
+        assert_eq!(count_3_measurement_sum_increased(&[]), 0);
+        assert_eq!(count_3_measurement_sum_increased(&[1]), 0);
+        assert_eq!(count_3_measurement_sum_increased(&[1, 2, 3]), 0);
+        assert_eq!(count_3_measurement_sum_increased(&[1, 2, 3, 4]), 1);
+        assert_eq!(count_3_measurement_sum_increased(&[199, 200, 208, 210, 200, 207, 240, 269, 260, 263]), 5);
     }
 }
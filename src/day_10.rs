@@ -1,8 +1,12 @@
 use std::collections::HashMap;
+use crate::input::PuzzleInput;
+use crate::registry::Example;
+use crate::solution::{Answer, Solution};
+use crate::utils::{column_of, error_at};
 
 
 enum SyntaxCheckResult {
-    CorruptedChunk(usize, char, Option<char>),
+    CorruptedChunk(char),
     Incomplete(Vec<char>),
 }
 
@@ -13,13 +17,13 @@ const AUTOCOMPLETE_POINTS: [u64; 4] = [1, 2, 3, 4];
 
 fn assemble_table<T, U>(arr1: [T; 4], arr2: [U; 4]) -> HashMap<T, U>
     where T: Eq + std::hash::Hash + Copy {
-    arr1.iter().map(|p| *p).zip(arr2).collect()
+    arr1.iter().copied().zip(arr2).collect()
 }
 
-fn check_syntax(line: &String) -> SyntaxCheckResult {
+fn check_syntax(line: &str) -> SyntaxCheckResult {
     let parens_table: HashMap<char, char> = assemble_table(OPEN_PAREN, CLOSE_PAREN);
     let mut open_parens = vec![];
-    for (i, paren) in line.chars().enumerate() {
+    for paren in line.chars() {
         if parens_table.contains_key(&paren) {
             open_parens.push(paren);
         } else {
@@ -29,8 +33,8 @@ fn check_syntax(line: &String) -> SyntaxCheckResult {
             };
             match find_expected_parens() {
                 Some(expected_paren) if *expected_paren == paren => {},
-                expected_paren_option => {
-                    return SyntaxCheckResult::CorruptedChunk(i, paren, expected_paren_option.map(|c| *c));
+                _ => {
+                    return SyntaxCheckResult::CorruptedChunk(paren);
                 },
             }
         }
@@ -38,32 +42,45 @@ fn check_syntax(line: &String) -> SyntaxCheckResult {
     SyntaxCheckResult::Incomplete(open_parens)
 }
 
-fn get_corrupted_chunk_points(lines: &Vec<String>) -> u64 {
+/// Parses the navigation subsystem, checking that each non-empty line is made only of brackets.
+pub fn parse_navigation_subsystem(lines: &[String]) -> anyhow::Result<Vec<String>> {
+    let mut chunks = vec![];
+    for (i, line) in lines.iter().enumerate() {
+        let trimmed = line.trim();
+        if trimmed.is_empty() {
+            continue;
+        }
+        if let Some((j, c)) = trimmed.char_indices().find(|(_, c)| !OPEN_PAREN.contains(c) && !CLOSE_PAREN.contains(c)) {
+            return Err(error_at(i, column_of(line, trimmed) + j, format!("expected a bracket, found '{}'", c)));
+        }
+        chunks.push(trimmed.to_string());
+    }
+    Ok(chunks)
+}
+
+/// Sums the syntax error score of the first illegal character in every corrupted line.
+pub fn get_corrupted_chunk_points(lines: &[String]) -> u64 {
     let points_table: HashMap<char, u64> = assemble_table(CLOSE_PAREN, CORRUPTED_CHUNK_POINTS);
     let corrupted_chunks = find_corrupted_chunks(lines);
     corrupted_chunks.into_iter().fold(0, |acc, close_parens| acc + points_table.get(&close_parens).unwrap_or(&0))
 }
 
-fn find_corrupted_chunks(lines: &Vec<String>) -> Vec<char> {
-    lines.into_iter().filter_map(|line| {
+fn find_corrupted_chunks(lines: &[String]) -> Vec<char> {
+    lines.iter().filter_map(|line| {
         let result = check_syntax(line);
         match result {
-            SyntaxCheckResult::CorruptedChunk(_, paren, _) => Some(paren),
+            SyntaxCheckResult::CorruptedChunk(paren) => Some(paren),
             _ => None,
         }
     }).collect()
 }
 
-pub fn part_1(lines: &Vec<String>) {
-    let points = get_corrupted_chunk_points(lines);
-    println!("Syntax Error Score (corrupted chunks): {}", points);
-}
-
 
-fn get_autocomplete_points(lines: &Vec<String>) -> u64 {
+/// Finds the middle score of the completion strings for every incomplete line.
+pub fn get_autocomplete_points(lines: &[String]) -> u64 {
     let parens_table: HashMap<char, char> = assemble_table(OPEN_PAREN, CLOSE_PAREN);
     let points_table: HashMap<char, u64> = assemble_table(CLOSE_PAREN, AUTOCOMPLETE_POINTS);
-    let mut all_scores: Vec<u64> = lines.into_iter().filter_map(|line| {
+    let mut all_scores: Vec<u64> = lines.iter().filter_map(|line| {
         let results = check_syntax(line);
         match results {
             SyntaxCheckResult::Incomplete(open_parens) => {
@@ -86,9 +103,48 @@ fn get_autocomplete_points(lines: &Vec<String>) -> u64 {
     all_scores[all_scores.len() / 2]
 }
 
-pub fn part_2(lines: &Vec<String>) {
-    let points = get_autocomplete_points(lines);
-    println!("Syntax Error Score (autocomplete): {}", points);
+/// Day 10: Syntax Scoring
+/// https://adventofcode.com/2021/day/10
+pub struct Day10;
+
+impl Solution for Day10 {
+    type Input = Vec<String>;
+
+    fn parse(input: &PuzzleInput) -> anyhow::Result<Self::Input> {
+        parse_navigation_subsystem(input.lines())
+    }
+
+    fn part_1(lines: &Self::Input) -> anyhow::Result<Answer> {
+        Ok(get_corrupted_chunk_points(lines).into())
+    }
+
+    fn part_2(lines: &Self::Input) -> anyhow::Result<Answer> {
+        Ok(get_autocomplete_points(lines).into())
+    }
+}
+
+register_day! {
+    day: 10,
+    title: "Syntax Scoring",
+    solution: Day10,
+    parts: [1, 2],
+    examples: [
+        Example {
+            input: r#"[({(<(())[]>[[{[]{<()<>>
+[(()[<>])]({[<{<<[]>>(
+{([(<{}[<>[]}>{[]{[(<()>
+(((({<>}<{<{<>}{[]{[]{}
+[[<[([]))<([[{}[[()]]]
+[{[{({}]{}}([{[{{{}}([]
+{<[[]]>}<{[{[{[]{()[[[]
+[<(<(<(<{}))><([]([]()
+<{([([[(<>()){}]>(<<{{
+<{([{{}}[<[[[<>{}]]]>[]]
+"#,
+            part_1: Some("26397"),
+            part_2: Some("288957"),
+        },
+    ],
 }
 
 
@@ -121,4 +177,10 @@ mod tests {
     fn test_autocomplete_points() {
         assert_eq!(get_autocomplete_points(&get_test_input()), 288957);
     }
+
+    #[test]
+    fn test_parse_errors() {
+        let lines = ["[({(<(())[]>[[{[]{<()<>>".to_string(), "[(()[<>]) ]".to_string()];
+        assert_eq!(parse_navigation_subsystem(&lines).unwrap_err().to_string(), "line 2, column 10: expected a bracket, found ' '");
+    }
 }
@@ -1,26 +1,27 @@
 use std::collections::{HashSet, HashMap};
 use std::iter::FromIterator;
 use petgraph::graph::{UnGraph, IndexType, NodeIndex};
+use crate::input::PuzzleInput;
+use crate::registry::Example;
+use crate::solution::{Answer, Solution};
+use crate::utils::error_at;
 
 
-pub fn part_1(lines: &Vec<String>) {
-    if let Some(count) = traverse_graph_1(lines) {
-        println!("Total distinct paths: {}", count);
-    } else {
-        println!("Failed to find paths!");
-    }
+/// Each line of the input is a path between two caves.
+pub fn parse_edges(lines: &[String]) -> anyhow::Result<Vec<(String, String)>> {
+    lines.iter().enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| {
+            let (from, to) = line.trim().split_once('-')
+                .ok_or_else(|| error_at(i, 0, format!("expected two caves separated by a '-', found '{}'", line.trim())))?;
+            Ok((from.to_string(), to.to_string()))
+        }).collect()
 }
 
-fn traverse_graph_1(lines: &Vec<String>) -> Option<u64> {
-    let lines_as_nodes: Vec<Vec<&str>> = lines.into_iter()
-        .filter_map(|line| {
-            let line_trimmed = line.trim();
-            if line_trimmed.is_empty() {
-                return None;
-            }
-            Some(line_trimmed.split('-').collect())
-        }).collect();
-    let unique_nodes = HashSet::<&str>::from_iter(lines_as_nodes.iter().map(|s| s.clone()).flatten());
+/// Counts the paths from `start` to `end` which visit each small cave at most once.
+pub fn traverse_graph_1(edges: &[(String, String)]) -> Option<u64> {
+    let lines_as_nodes: Vec<Vec<&str>> = edges.iter().map(|(from, to)| vec![from.as_str(), to.as_str()]).collect();
+    let unique_nodes = HashSet::<&str>::from_iter(lines_as_nodes.iter().flat_map(|s| s.iter().copied()));
     let nodes = unique_nodes.into_iter().collect::<Vec<&str>>();
     let nodes_by_name: HashMap<&str, NodeIndex> = nodes.iter().enumerate().map(|(i, name)| (*name, NodeIndex::new(i))).collect();
     let start_node_index = nodes_by_name.get("start")?;
@@ -42,7 +43,7 @@ fn traverse_graph_1(lines: &Vec<String>) -> Option<u64> {
         }
 
         let neighbors = graph.neighbors(current_index);
-        let visitable_neighbors = neighbors.into_iter().filter(|neighbor| {
+        let visitable_neighbors = neighbors.filter(|neighbor| {
             let neighbor_node = nodes[neighbor.index()];
             neighbor_node.chars().all(|c| c.is_ascii_uppercase()) || !path.contains(neighbor)
         });
@@ -57,15 +58,7 @@ fn traverse_graph_1(lines: &Vec<String>) -> Option<u64> {
 
 
 
-pub fn part_2(lines: &Vec<String>) {
-    if let Some(count) = traverse_graph_2(lines) {
-        println!("Total distinct paths: {}", count);
-    } else {
-        println!("Failed to find paths!");
-    }
-}
-
-fn vec_is_unique<T>(vec: &Vec<T>) -> bool
+fn vec_is_unique<T>(vec: &[T]) -> bool
    where T: Eq + std::hash::Hash + Copy {
     let mut found: HashSet<T> = HashSet::new();
     for el in vec {
@@ -77,16 +70,10 @@ fn vec_is_unique<T>(vec: &Vec<T>) -> bool
     true
 }
 
-fn traverse_graph_2(lines: &Vec<String>) -> Option<u64> {
-    let lines_as_nodes: Vec<Vec<&str>> = lines.into_iter()
-        .filter_map(|line| {
-            let line_trimmed = line.trim();
-            if line_trimmed.is_empty() {
-                return None;
-            }
-            Some(line_trimmed.split('-').collect())
-        }).collect();
-    let unique_nodes = HashSet::<&str>::from_iter(lines_as_nodes.iter().map(|s| s.clone()).flatten());
+/// Counts the paths from `start` to `end` which visit a single small cave at most twice, and every other small cave at most once.
+pub fn traverse_graph_2(edges: &[(String, String)]) -> Option<u64> {
+    let lines_as_nodes: Vec<Vec<&str>> = edges.iter().map(|(from, to)| vec![from.as_str(), to.as_str()]).collect();
+    let unique_nodes = HashSet::<&str>::from_iter(lines_as_nodes.iter().flat_map(|s| s.iter().copied()));
     let nodes = unique_nodes.into_iter().collect::<Vec<&str>>();
     let nodes_by_name: HashMap<&str, NodeIndex> = nodes.iter().enumerate().map(|(i, name)| (*name, NodeIndex::new(i))).collect();
     let start_node_index = nodes_by_name.get("start")?;
@@ -108,7 +95,7 @@ fn traverse_graph_2(lines: &Vec<String>) -> Option<u64> {
         }
 
         let neighbors = graph.neighbors(current_index);
-        let visitable_neighbors = neighbors.into_iter().filter(|neighbor| {
+        let visitable_neighbors = neighbors.filter(|neighbor| {
             let neighbor_name = nodes[neighbor.index()];
             let is_start_or_end = neighbor == start_node_index || neighbor == end_node_index;
             let path_smalls: Vec<&str> = path.iter().filter(|i| *i != start_node_index && *i != end_node_index && !nodes[i.index()].chars().all(|c| c.is_ascii_uppercase())).map(|i| nodes[i.index()]).collect();
@@ -131,6 +118,87 @@ fn traverse_graph_2(lines: &Vec<String>) -> Option<u64> {
     Some(paths_completed.len() as u64)
 }
 
+/// Day 12: Passage Pathing
+/// https://adventofcode.com/2021/day/12
+pub struct Day12;
+
+impl Solution for Day12 {
+    type Input = Vec<(String, String)>;
+
+    fn parse(input: &PuzzleInput) -> anyhow::Result<Self::Input> {
+        parse_edges(input.lines())
+    }
+
+    fn part_1(edges: &Self::Input) -> anyhow::Result<Answer> {
+        let count = traverse_graph_1(edges).ok_or_else(|| anyhow::anyhow!("Failed to find paths!"))?;
+        Ok(count.into())
+    }
+
+    fn part_2(edges: &Self::Input) -> anyhow::Result<Answer> {
+        let count = traverse_graph_2(edges).ok_or_else(|| anyhow::anyhow!("Failed to find paths!"))?;
+        Ok(count.into())
+    }
+}
+
+register_day! {
+    day: 12,
+    title: "Passage Pathing",
+    solution: Day12,
+    parts: [1, 2],
+    examples: [
+        Example {
+            input: r#"start-A
+start-b
+A-c
+A-b
+b-d
+A-end
+b-end
+"#,
+            part_1: Some("10"),
+            part_2: Some("36"),
+        },
+        Example {
+            input: r#"dc-end
+HN-start
+start-kj
+dc-start
+dc-HN
+LN-dc
+HN-end
+kj-sa
+kj-HN
+kj-dc
+"#,
+            part_1: Some("19"),
+            part_2: Some("103"),
+        },
+        Example {
+            input: r#"fs-end
+he-DX
+fs-he
+start-DX
+pj-DX
+end-zg
+zg-sl
+zg-pj
+pj-he
+RW-he
+fs-DX
+pj-RW
+zg-RW
+start-pj
+he-WI
+zg-he
+pj-fs
+start-RW
+"#,
+            part_1: Some("226"),
+            part_2: Some("3509"),
+        },
+    ],
+}
+
 
 #[cfg(test)]
 mod tests {
@@ -177,8 +245,8 @@ pj-fs
 start-RW
 "#;
 
-    fn get_test_input(s: &str) -> Vec<String> {
-        s.split_whitespace().map(str::to_string).collect()
+    fn get_test_input(s: &str) -> Vec<(String, String)> {
+        parse_edges(&s.split_whitespace().map(str::to_string).collect::<Vec<String>>()).unwrap()
     }
 
     #[test]
@@ -194,4 +262,10 @@ start-RW
         assert_eq!(traverse_graph_2(&get_test_input(TEST_INPUT_2)), Some(103));
         assert_eq!(traverse_graph_2(&get_test_input(TEST_INPUT_3)), Some(3509));
     }
+
+    #[test]
+    fn test_parse_errors() {
+        let lines = ["start-A".to_string(), "A end".to_string()];
+        assert_eq!(parse_edges(&lines).unwrap_err().to_string(), "line 2, column 1: expected two caves separated by a '-', found 'A end'");
+    }
 }
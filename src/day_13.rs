@@ -1,19 +1,67 @@
 use std::collections::HashSet;
+use crate::input::PuzzleInput;
+use crate::registry::Example;
+use crate::solution::{Answer, Solution};
+use crate::utils::{column_of, error_at, parse_at};
 
 
-pub fn part_1(lines: &Vec<String>) {
-    let count = perform_first_fold(lines);
-    println!("Visible dots: {}", count);
-}
+/// Day 13: Transparent Origami
+/// https://adventofcode.com/2021/day/13
+pub struct Day13;
+
+impl Solution for Day13 {
+    type Input = Manual;
+
+    fn parse(input: &PuzzleInput) -> anyhow::Result<Self::Input> {
+        parse_input(input)
+    }
+
+    fn part_1(manual: &Self::Input) -> anyhow::Result<Answer> {
+        Ok(perform_first_fold(manual).into())
+    }
 
-pub fn part_2(lines: &Vec<String>) {
-    let paper = perform_all_folds(lines);
-    let success = visualize_paper(&paper);
-    if success.is_none() {
-        println!("Failed to visualize paper!");
+    fn part_2(manual: &Self::Input) -> anyhow::Result<Answer> {
+        let paper = perform_all_folds(manual);
+        let visualized = visualize_paper(&paper).ok_or_else(|| anyhow::anyhow!("Failed to visualize paper!"))?;
+        Ok(Answer::Art(visualized))
     }
 }
 
+register_day! {
+    day: 13,
+    title: "Transparent Origami",
+    solution: Day13,
+    parts: [1, 2],
+    examples: [
+        Example {
+            input: r#"6,10
+0,14
+9,10
+0,3
+10,4
+4,11
+6,0
+6,12
+4,1
+0,13
+10,12
+3,4
+3,0
+8,4
+1,10
+2,14
+8,10
+9,0
+
+fold along y=7
+fold along x=5
+"#,
+            part_1: Some("17"),
+            part_2: Some("#####\n#...#\n#...#\n#...#\n#####"),
+        },
+    ],
+}
+
 
 #[derive(Debug, PartialEq)]
 enum Direction {
@@ -27,66 +75,72 @@ struct FoldInstruction {
     position: usize,
 }
 
-fn visualize_paper(paper: &HashSet<(usize, usize)>) -> Option<()> {
+/// The dots on the transparent paper, and the instructions for folding it.
+pub struct Manual {
+    paper: HashSet<(usize, usize)>,
+    instructions: Vec<FoldInstruction>,
+}
+
+/// Draws the dots on the paper, one string per row.
+pub fn visualize_paper(paper: &HashSet<(usize, usize)>) -> Option<Vec<String>> {
     let max_x = paper.iter().map(|(x, _)| *x).max()?;
     let max_y = paper.iter().map(|(_, y)| *y).max()?;
-    for y in 0..max_y + 1 {
-        for x in 0..max_x + 1 {
-            print!("{}", if paper.contains(&(x, y)) { "#" } else { "." });
-        }
-        println!();
-    }
-    Some(())
+    let rows = (0..max_y + 1).map(|y| {
+        (0..max_x + 1).map(|x| if paper.contains(&(x, y)) { '#' } else { '.' }).collect::<String>()
+    }).collect();
+    Some(rows)
 }
 
-fn perform_first_fold(lines: &Vec<String>) -> u64 {
-    let (paper, instructions) = parse_input(lines);
-    perform_instructions_n(&paper.into_iter().collect::<HashSet<(usize, usize)>>(), &instructions, 1).len() as u64
+/// Counts the dots that are visible after only the first fold.
+pub fn perform_first_fold(manual: &Manual) -> u64 {
+    perform_instructions_n(&manual.paper, &manual.instructions, 1).len() as u64
 }
 
-fn perform_all_folds(lines: &Vec<String>) -> HashSet<(usize, usize)> {
-    let (paper, instructions) = parse_input(lines);
-    perform_instructions_n(&paper.into_iter().collect::<HashSet<(usize, usize)>>(), &instructions, usize::MAX)
+/// Returns the dots left after following every fold instruction.
+pub fn perform_all_folds(manual: &Manual) -> HashSet<(usize, usize)> {
+    perform_instructions_n(&manual.paper, &manual.instructions, usize::MAX)
 }
 
-fn parse_input(lines: &Vec<String>) -> (Vec<(usize, usize)>, Vec<FoldInstruction>) {
-    let mut paper = vec![];
-    let mut paper_done = false;
-    let mut instructions = vec![];
+/// Parses the dot coordinates, then the fold instructions after the blank line.
+pub fn parse_input(input: &PuzzleInput) -> anyhow::Result<Manual> {
+    let sections = input.sections();
+    let (dots, folds) = match sections.as_slice() {
+        [dots, folds] => (dots, folds),
+        _ => return Err(anyhow::anyhow!("Expected the dots and fold instructions separated by a blank line, found {} sections", sections.len())),
+    };
 
-    for line in lines {
+    let mut paper = HashSet::new();
+    for (i, line) in (dots.first_line..).zip(dots.lines) {
         let trimmed = line.trim();
-
-        // Skip any empty lines at the beginning
-        if paper.is_empty() && trimmed.is_empty() {
-            continue;
-        }
-
-        if !paper_done && !trimmed.is_empty() {
-            let coords: Vec<usize> = trimmed.split(",").map(|s| s.parse::<usize>().unwrap()).collect();
-            paper.push((coords[0], coords[1]));
-            continue;
+        let coords = trimmed.split(',').map(|s| parse_at(i, line, s)).collect::<anyhow::Result<Vec<usize>>>()?;
+        if coords.len() != 2 {
+            return Err(error_at(i, column_of(line, trimmed), format!("expected a dot as two numbers separated by a comma, found '{}'", trimmed)));
         }
+        paper.insert((coords[0], coords[1]));
+    }
 
-        // Skip empty line between paper and instructions
-        if instructions.is_empty() && trimmed.is_empty() {
-            paper_done = true;
-            continue;
-        }
+    let instructions = (folds.first_line..).zip(folds.lines)
+        .map(|(i, line)| parse_fold_instruction(i, line))
+        .collect::<anyhow::Result<Vec<FoldInstruction>>>()?;
 
-        if !trimmed.is_empty() {
-            let separator_index = trimmed.find('=').unwrap();
-            let (instruction_1, instruction_2) = trimmed.split_at(separator_index);
-            let direction = if instruction_1.get(instruction_1.len() - 1..instruction_1.len()).unwrap() == "y" { Direction::Up } else { Direction::Left };
-            let position = instruction_2[1..].parse::<usize>().unwrap();
-            instructions.push(FoldInstruction { direction: direction, position: position });
-        }
-    }
+    Ok(Manual { paper, instructions })
+}
 
-    (paper, instructions)
+/// Parses an instruction such as `fold along y=7`, found at line `line_index` of the input.
+fn parse_fold_instruction(line_index: usize, line: &str) -> anyhow::Result<FoldInstruction> {
+    let trimmed = line.trim();
+    let fold = trimmed.strip_prefix("fold along ")
+        .ok_or_else(|| error_at(line_index, column_of(line, trimmed), format!("expected a fold instruction, found '{}'", trimmed)))?;
+    let direction = match fold.get(..2) {
+        Some("x=") => Direction::Left,
+        Some("y=") => Direction::Up,
+        _ => return Err(error_at(line_index, column_of(line, fold), format!("expected the fold to be along x= or y=, found '{}'", fold))),
+    };
+    let position = parse_at(line_index, line, &fold[2..])?;
+    Ok(FoldInstruction { direction, position })
 }
 
-fn perform_instructions_n(paper: &HashSet<(usize, usize)>, instructions: &Vec<FoldInstruction>, n: usize) -> HashSet<(usize, usize)> {
+fn perform_instructions_n(paper: &HashSet<(usize, usize)>, instructions: &[FoldInstruction], n: usize) -> HashSet<(usize, usize)> {
     let mut current_paper = paper.clone();
     for instruction in instructions.iter().take(n) {
         let mut new_paper = HashSet::new();
@@ -95,7 +149,7 @@ fn perform_instructions_n(paper: &HashSet<(usize, usize)>, instructions: &Vec<Fo
                 if y <= &instruction.position {
                     new_paper.insert((*x, *y));
                 } else {
-                    new_paper.insert((*x, (instruction.position * 2 - y) as usize));
+                    new_paper.insert((*x, instruction.position * 2 - y));
                 }
             }
         } else {
@@ -103,7 +157,7 @@ fn perform_instructions_n(paper: &HashSet<(usize, usize)>, instructions: &Vec<Fo
                 if x <= &instruction.position {
                     new_paper.insert((*x, *y));
                 } else {
-                    new_paper.insert(((instruction.position * 2 - x) as usize, *y));
+                    new_paper.insert((instruction.position * 2 - x, *y));
                 }
             }
         }
@@ -140,12 +194,35 @@ fold along y=7
 fold along x=5
 "#;
 
-    fn get_test_input(s: &str) -> Vec<String> {
-        s.split("\n").map(str::to_string).collect()
+    fn get_test_input(s: &str) -> Manual {
+        parse_input(&PuzzleInput::from(s)).unwrap()
     }
 
     #[test]
     fn test_part_1() {
         assert_eq!(perform_first_fold(&get_test_input(TEST_INPUT_1)), 17);
     }
+
+    #[test]
+    fn test_part_2() {
+        let paper = perform_all_folds(&get_test_input(TEST_INPUT_1));
+        assert_eq!(visualize_paper(&paper), Some(vec![
+            "#####".to_string(),
+            "#...#".to_string(),
+            "#...#".to_string(),
+            "#...#".to_string(),
+            "#####".to_string(),
+        ]));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let parse = |s: &str| parse_input(&PuzzleInput::from(s)).err().unwrap().to_string();
+        assert_eq!(parse("6,10\n0,14,3\n\nfold along y=7"), "line 2, column 1: expected a dot as two numbers separated by a comma, found '0,14,3'");
+        assert_eq!(parse("6,10\n0,-14\n\nfold along y=7"), "line 2, column 3: could not parse '-14': invalid digit found in string");
+        assert_eq!(parse("6,10\n\nfold along z=7"), "line 3, column 12: expected the fold to be along x= or y=, found 'z=7'");
+        assert_eq!(parse("6,10\n\nfold along y=seven"), "line 3, column 14: could not parse 'seven': invalid digit found in string");
+        assert_eq!(parse("6,10\n\nturn along y=7"), "line 3, column 1: expected a fold instruction, found 'turn along y=7'");
+        assert_eq!(parse("6,10\n0,14\n"), "Expected the dots and fold instructions separated by a blank line, found 1 sections");
+    }
 }
@@ -1,33 +1,91 @@
 use std::collections::HashMap;
+use crate::input::PuzzleInput;
+use crate::registry::Example;
+use crate::solution::{Answer, Solution};
+use crate::utils::{column_of, error_at};
 
 
-pub fn part_1(lines: &Vec<String>) {
-    if let Some(stats) = stats_after_n_insertions(lines, 10) {
-        println!("Stats: {:?}, Diff: {}", stats, stats.mce_count - stats.lce_count);
-    } else {
-        println!("Failed to find stats!");
+/// Day 14: Extended Polymerization
+/// https://adventofcode.com/2021/day/14
+pub struct Day14;
+
+impl Solution for Day14 {
+    type Input = Polymer;
+
+    fn parse(input: &PuzzleInput) -> anyhow::Result<Self::Input> {
+        parse_input(input)
+    }
+
+    fn part_1(polymer: &Self::Input) -> anyhow::Result<Answer> {
+        let stats = stats_after_n_insertions(polymer, 10).ok_or_else(|| anyhow::anyhow!("Failed to find stats!"))?;
+        Ok(stats.to_answer())
     }
-}
 
-pub fn part_2(lines: &Vec<String>) {
-    if let Some(stats) = stats_after_n_insertions(lines, 40) {
-        println!("Stats: {:?}, Diff: {}", stats, stats.mce_count - stats.lce_count);
-    } else {
-        println!("Failed to find stats!");
+    fn part_2(polymer: &Self::Input) -> anyhow::Result<Answer> {
+        let stats = stats_after_n_insertions(polymer, 40).ok_or_else(|| anyhow::anyhow!("Failed to find stats!"))?;
+        Ok(stats.to_answer())
     }
 }
 
-fn stats_after_n_insertions(lines: &Vec<String>, n: usize) -> Option<PolymerStats> {
-    let (mut counts, rules, first_element, last_element) = parse_input(lines);
+register_day! {
+    day: 14,
+    title: "Extended Polymerization",
+    solution: Day14,
+    parts: [1, 2],
+    examples: [
+        Example {
+            input: r#"NNCB
+
+CH -> B
+HH -> N
+CB -> H
+NH -> C
+HB -> C
+HC -> B
+HN -> C
+NN -> C
+BH -> H
+NC -> B
+NB -> B
+BN -> B
+BB -> N
+BC -> B
+CC -> N
+CN -> C
+"#,
+            part_1: Some("1588"),
+            part_2: Some("2188189693529"),
+        },
+    ],
+}
+
+/// Performs `n` steps of pair insertion and returns the most and least common elements afterwards.
+pub fn stats_after_n_insertions(polymer: &Polymer, n: usize) -> Option<PolymerStats> {
+    let mut counts = polymer.pair_counts.clone();
     for _ in 0..n {
-        insert_pairs_once(&mut counts, &rules);
+        insert_pairs_once(&mut counts, &polymer.rules);
     }
-    get_polymer_stats(&counts, first_element, last_element)
+    get_polymer_stats(&counts, polymer.first_element, polymer.last_element)
 }
 
 
-fn parse_input(lines: &Vec<String>) -> (HashMap<(char, char), usize>, HashMap<(char, char), char>, char, char) {
-    let polymer_template = lines[0].chars().collect::<Vec<char>>();
+/// The polymer template, stored as counts of each pair of adjacent elements (along with the
+/// elements at either end, which are the only ones not counted twice), and the insertion rules.
+pub struct Polymer {
+    pair_counts: HashMap<(char, char), usize>,
+    rules: HashMap<(char, char), char>,
+    first_element: char,
+    last_element: char,
+}
+
+/// Parses the polymer template from the first line, and the pair insertion rules after the blank line.
+pub fn parse_input(input: &PuzzleInput) -> anyhow::Result<Polymer> {
+    let sections = input.sections();
+    let (template, rule_lines) = match sections.as_slice() {
+        [template, rules] if template.lines.len() == 1 => (template, rules),
+        _ => return Err(anyhow::anyhow!("Expected the polymer template on one line, then the rules after a blank line")),
+    };
+    let polymer_template = template.lines[0].trim().chars().collect::<Vec<char>>();
     let first_element = polymer_template[0];
     let last_element = polymer_template[polymer_template.len() - 1];
     let mut counts: HashMap<(char, char), usize> = HashMap::new();
@@ -36,18 +94,22 @@ fn parse_input(lines: &Vec<String>) -> (HashMap<(char, char), usize>, HashMap<(c
     }
 
     let mut rules: HashMap<(char, char), char> = HashMap::new();
-    for rule in lines[2..].iter() {
-        let rule_parts = rule.split(" -> ").collect::<Vec<&str>>();
-        if rule_parts.len() != 2 {
-            continue;
+    for (i, rule) in (rule_lines.first_line..).zip(rule_lines.lines) {
+        let trimmed = rule.trim();
+        let (pair, element) = trimmed.split_once(" -> ")
+            .ok_or_else(|| error_at(i, column_of(rule, trimmed), format!("expected a rule such as 'CH -> B', found '{}'", trimmed)))?;
+        let pair = pair.chars().collect::<Vec<char>>();
+        if pair.len() != 2 {
+            return Err(error_at(i, column_of(rule, trimmed), format!("expected a pair of two elements, found {}", pair.len())));
         }
-        let pair = rule_parts[0].chars().collect::<Vec<char>>();
-        if let Some(element) = rule_parts[1].chars().next() {
-            rules.insert((pair[0], pair[1]), element);
+        let element = element.chars().collect::<Vec<char>>();
+        if element.len() != 1 {
+            return Err(error_at(i, column_of(rule, trimmed) + trimmed.len() - element.len(), format!("expected a single element to insert, found {}", element.len())));
         }
+        rules.insert((pair[0], pair[1]), element[0]);
     }
 
-    (counts, rules, first_element, last_element)
+    Ok(Polymer { pair_counts: counts, rules, first_element, last_element })
 }
 
 fn insert_pairs_once(counts: &mut HashMap<(char, char), usize>, insertion_rules: &HashMap<(char, char), char>) {
@@ -66,21 +128,32 @@ fn insert_pairs_once(counts: &mut HashMap<(char, char), usize>, insertion_rules:
     }
 }
 
+/// The most and least common elements of a polymer, and how many times each appears.
 #[derive(Debug, PartialEq)]
-struct PolymerStats {
-    most_common_element: char,
-    mce_count: u64,
-    least_common_element: char,
-    lce_count: u64,
+pub struct PolymerStats {
+    pub most_common_element: char,
+    pub mce_count: u64,
+    pub least_common_element: char,
+    pub lce_count: u64,
 }
 
 impl PolymerStats {
+    /// The difference between the counts, with the elements and their counts as details.
+    fn to_answer(&self) -> Answer {
+        Answer::from(self.mce_count - self.lce_count).with_details(vec![
+            ("most_common_element", self.most_common_element.into()),
+            ("most_common_count", self.mce_count.into()),
+            ("least_common_element", self.least_common_element.into()),
+            ("least_common_count", self.lce_count.into()),
+        ])
+    }
+
     fn new(mce: char, mce_count: u64, lce: char, lce_count: u64) -> Self {
         Self {
             most_common_element: mce,
-            mce_count: mce_count,
+            mce_count,
             least_common_element: lce,
-            lce_count: lce_count,
+            lce_count,
         }
     }
 }
@@ -138,8 +211,8 @@ CC -> N
 CN -> C
 "#;
 
-    fn get_test_input(s: &str) -> Vec<String> {
-        s.split("\n").map(str::to_string).collect()
+    fn get_test_input(s: &str) -> Polymer {
+        parse_input(&PuzzleInput::from(s)).unwrap()
     }
 
     #[test]
@@ -151,4 +224,13 @@ CN -> C
     fn test_part_2() {
         assert_eq!(stats_after_n_insertions(&get_test_input(TEST_INPUT_1), 40), Some(PolymerStats::new('B', 2192039569602, 'H', 3849876073)));
     }
+
+    #[test]
+    fn test_parse_errors() {
+        let parse = |s: &str| parse_input(&PuzzleInput::from(s)).err().unwrap().to_string();
+        assert_eq!(parse("\n\nCH -> B"), "Expected the polymer template on one line, then the rules after a blank line");
+        assert_eq!(parse("NNCB\n\nCH -> B\nHH => N"), "line 4, column 1: expected a rule such as 'CH -> B', found 'HH => N'");
+        assert_eq!(parse("NNCB\n\nCHH -> B"), "line 3, column 1: expected a pair of two elements, found 3");
+        assert_eq!(parse("NNCB\n\nCH -> BB"), "line 3, column 7: expected a single element to insert, found 2");
+    }
 }
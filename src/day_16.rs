@@ -1,200 +1,305 @@
-use bitvec::prelude::*;
+use std::fmt;
+use crate::input::PuzzleInput;
+use crate::registry::Example;
+use crate::solution::{Answer, Solution};
+use crate::utils::{column_of, error_at};
 
 
-pub fn part_1(lines: &Vec<String>) {
-    let sum = sum_version_numbers(lines);
-    println!("Sum of Version Numbers: {}", sum);
+/// Day 16: Packet Decoder
+/// https://adventofcode.com/2021/day/16
+pub struct Day16;
+
+impl Solution for Day16 {
+    type Input = Vec<Packet>;
+
+    fn parse(input: &PuzzleInput) -> anyhow::Result<Self::Input> {
+        parse_transmission(input.lines())
+    }
+
+    fn part_1(packets: &Self::Input) -> anyhow::Result<Answer> {
+        Ok(sum_version_numbers(packets).into())
+    }
+
+    fn part_2(packets: &Self::Input) -> anyhow::Result<Answer> {
+        Ok(calc_packets(packets)?.into())
+    }
 }
 
-pub fn part_2(lines: &Vec<String>) {
-    println!("Calculated: {}", calc_packets(lines));
+register_day! {
+    day: 16,
+    title: "Packet Decoder",
+    solution: Day16,
+    parts: [1, 2],
+    examples: [
+        Example { input: "8A004A801A8002F478\n", part_1: Some("16"), part_2: None },
+        Example { input: "620080001611562C8802118E34\n", part_1: Some("12"), part_2: None },
+        Example { input: "C0015000016115A2E0802F182340\n", part_1: Some("23"), part_2: None },
+        Example { input: "A0016C880162017C3686B18A3D4780\n", part_1: Some("31"), part_2: None },
+        Example { input: "C200B40A82\n", part_1: None, part_2: Some("3") },
+        Example { input: "04005AC33890\n", part_1: None, part_2: Some("54") },
+        Example { input: "880086C3E88112\n", part_1: None, part_2: Some("7") },
+        Example { input: "CE00C43D881120\n", part_1: None, part_2: Some("9") },
+        Example { input: "D8005AC2A8F0\n", part_1: None, part_2: Some("1") },
+        Example { input: "F600BC2D8F\n", part_1: None, part_2: Some("0") },
+        Example { input: "9C005AC2F8F0\n", part_1: None, part_2: Some("0") },
+        Example { input: "9C0141080250320F1802104A08\n", part_1: None, part_2: Some("1") },
+    ],
 }
 
 
-struct Packet {
-    version: BitVec,
-    type_id: BitVec,
-    subpackets: Vec<Box<Packet>>,
-    value: Option<u64>,
+/// The operation an operator packet applies to the values of its subpackets.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Operator {
+    Sum,
+    Product,
+    Min,
+    Max,
+    /// 1 if the first value is greater than the second, otherwise 0.
+    Gt,
+    /// 1 if the first value is less than the second, otherwise 0.
+    Lt,
+    /// 1 if the two values are equal, otherwise 0.
+    Eq,
 }
 
-impl Packet {
-    fn new(version: &BitSlice, type_id: &BitSlice, subpackets: Vec<Box<Packet>>, value: Option<u64>) -> Self {
-        Packet { version: BitVec::from_bitslice(version), type_id: BitVec::from_bitslice(type_id), subpackets: subpackets, value: value }
+impl Operator {
+    /// The operator for a packet's type ID, or `None` for type ID 4, which is a literal value.
+    pub fn from_type_id(type_id: u64) -> Option<Operator> {
+        match type_id {
+            0 => Some(Operator::Sum),
+            1 => Some(Operator::Product),
+            2 => Some(Operator::Min),
+            3 => Some(Operator::Max),
+            5 => Some(Operator::Gt),
+            6 => Some(Operator::Lt),
+            7 => Some(Operator::Eq),
+            _ => None,
+        }
     }
 
-    fn version_value(&self) -> u8 {
-        self.version.load::<u8>().reverse_bits() >> 5
+    /// Applies the operator to the values of the subpackets, failing if there are the wrong number
+    /// of them or the result doesn't fit in a `u64`.
+    pub fn apply(self, values: &[u64]) -> anyhow::Result<u64> {
+        let too_large = || anyhow::anyhow!("The {} of {:?} does not fit in 64 bits", self, values);
+        match self {
+            Operator::Sum => values.iter().try_fold(0u64, |acc, value| acc.checked_add(*value)).ok_or_else(too_large),
+            Operator::Product => values.iter().try_fold(1u64, |acc, value| acc.checked_mul(*value)).ok_or_else(too_large),
+            Operator::Min | Operator::Max => {
+                let result = if self == Operator::Min { values.iter().min() } else { values.iter().max() };
+                result.copied().ok_or_else(|| anyhow::anyhow!("Expected the {} of at least 1 value, found none", self))
+            },
+            Operator::Gt | Operator::Lt | Operator::Eq => match values {
+                [first, second] => Ok(match self {
+                    Operator::Gt => first > second,
+                    Operator::Lt => first < second,
+                    _ => first == second,
+                } as u64),
+                _ => Err(anyhow::anyhow!("Expected '{}' to compare 2 values, found {}", self, values.len())),
+            },
+        }
     }
+}
 
-    fn type_id_value(&self) -> u8 {
-        self.type_id.load::<u8>().reverse_bits() >> 5
+impl fmt::Display for Operator {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Operator::Sum => "sum",
+            Operator::Product => "product",
+            Operator::Min => "minimum",
+            Operator::Max => "maximum",
+            Operator::Gt => "greater than",
+            Operator::Lt => "less than",
+            Operator::Eq => "equal to",
+        };
+        write!(f, "{}", name)
     }
+}
 
-    fn sum_versions(&self) -> u64 {
-        self.subpackets.iter().fold(self.version_value() as u64, |acc, packet| acc + packet.sum_versions())
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Packet {
+    Literal { version: u8, value: u64 },
+    Operator { version: u8, op: Operator, children: Vec<Packet> },
+}
+
+impl Packet {
+    pub fn version(&self) -> u8 {
+        match self {
+            Packet::Literal { version, .. } | Packet::Operator { version, .. } => *version,
+        }
     }
 
-    fn calc_packet(&self) -> u64 {
-        let type_id_value = self.type_id_value();
-        match type_id_value {
-            0 => { // sum
-                self.subpackets.iter().fold(0, |acc, packet| acc + packet.calc_packet())
-            },
-            1 => { // product
-                self.subpackets.iter().fold(1, |acc, packet| acc * packet.calc_packet())
-            },
-            2 => { // min
-                self.subpackets.iter().map(|packet| packet.calc_packet()).min().unwrap()
-            },
-            3 => { // max
-                self.subpackets.iter().map(|packet| packet.calc_packet()).max().unwrap()
-            },
-            4 => { // literal
-                self.value.unwrap()
-            },
-            5 => { // greater than
-                let first = self.subpackets[0].calc_packet();
-                let second = self.subpackets[1].calc_packet();
-                if first > second { 1 } else { 0 }
-            },
-            6 => { // less than
-                let first = self.subpackets[0].calc_packet();
-                let second = self.subpackets[1].calc_packet();
-                if first < second { 1 } else { 0 }
-            },
-            7 => { // equal to
-                let first = self.subpackets[0].calc_packet();
-                let second = self.subpackets[1].calc_packet();
-                if first == second { 1 } else { 0 }
+    /// The packet's version plus those of all of its subpackets.
+    pub fn sum_versions(&self) -> u64 {
+        match self {
+            Packet::Literal { version, .. } => *version as u64,
+            Packet::Operator { version, children, .. } => children.iter().fold(*version as u64, |acc, packet| acc + packet.sum_versions()),
+        }
+    }
+
+    /// Evaluates the expression the packet represents.
+    pub fn eval(&self) -> anyhow::Result<u64> {
+        match self {
+            Packet::Literal { value, .. } => Ok(*value),
+            Packet::Operator { op, children, .. } => {
+                let values = children.iter().map(Packet::eval).collect::<anyhow::Result<Vec<u64>>>()?;
+                op.apply(&values)
             },
-            _ => { // this should never happen
-                panic!("Type ID {} not supported!", type_id_value);
-            }
         }
     }
 
-    fn fmt_sub(&self, f: &mut std::fmt::Formatter, layer: usize) -> std::fmt::Result {
-        let version_fmt = self.version_value();
-        let type_id_fmt = match self.type_id_value() {
-            0 => "Sum",
-            1 => "Product",
-            2 => "Minimum",
-            3 => "Maximum",
-            4 => "Literal",
-            5 => "Greater Than",
-            6 => "Less Than",
-            7 => "Equal To",
-            _ => "Unknown",
-        };
-        let _ = f.write_fmt(format_args!("{:indent$}Version {} TypeID {} Value {:?} / Raw: {} {}\n{:indent$}Sub Packets:\n", "", version_fmt, type_id_fmt, self.value, self.version, self.type_id, "", indent=2*layer))?;
-        let subpacket_results = self.subpackets.iter().fold(Ok(()), |acc, packet: &Box<Packet>| {
-            if acc.is_err() {
-                acc
-            } else {
-                packet.fmt_sub(f, layer + 1)
-            }
-        })?;
-        Ok(subpacket_results)
+    fn fmt_indented(&self, f: &mut fmt::Formatter, depth: usize) -> fmt::Result {
+        match self {
+            Packet::Literal { version, value } => writeln!(f, "{:indent$}{} (version {})", "", value, version, indent = 2 * depth),
+            Packet::Operator { version, op, children } => {
+                writeln!(f, "{:indent$}{} (version {})", "", op, version, indent = 2 * depth)?;
+                children.iter().try_for_each(|packet| packet.fmt_indented(f, depth + 1))
+            },
+        }
     }
 }
 
-impl std::fmt::Debug for Packet {
-    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        self.fmt_sub(f, 0)
+/// Prints the packet as a tree, with each subpacket on its own line indented under its operator.
+impl fmt::Display for Packet {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.fmt_indented(f, 0)
     }
 }
 
-fn sum_version_numbers(lines: &Vec<String>) -> u64 {
-    let packets = parse_transmission(lines);
-    packets.iter().fold(0, |acc, packet| acc + packet.sum_versions())
+/// Sums the version numbers of every packet, including subpackets.
+pub fn sum_version_numbers(packets: &[Packet]) -> u64 {
+    packets.iter().map(|packet| packet.sum_versions()).sum()
 }
 
-fn calc_packets(lines: &Vec<String>) -> u64 {
-    let packets = parse_transmission(lines);
-    packets[0].calc_packet()
+/// Evaluates the expression represented by the outermost packet.
+pub fn calc_packets(packets: &[Packet]) -> anyhow::Result<u64> {
+    packets.first().ok_or_else(|| anyhow::anyhow!("The transmission has no packets"))?.eval()
 }
 
-fn parse_transmission(lines: &Vec<String>) -> Vec<Box<Packet>> {
-    let transmission = lines.iter().filter(|line| !line.trim().is_empty()).next().unwrap();
-    let mut bv = BitVec::new();
-    let as_half_bytes: Vec<u8> = transmission.chars().filter_map(|c| c.to_digit(16).map(|i| i as u8)).collect();
-    bv.resize(as_half_bytes.len() * 4, false);
-    for (i, half_byte) in as_half_bytes.iter().enumerate() {
-        bv[i * 4..(i + 1) * 4].store(half_byte.reverse_bits() >> 4);
+/// Decodes the hexadecimal transmission on the first non-empty line into its packets.
+pub fn parse_transmission(lines: &[String]) -> anyhow::Result<Vec<Packet>> {
+    let (line_index, line) = lines.iter().enumerate().find(|(_, line)| !line.trim().is_empty())
+        .ok_or_else(|| anyhow::anyhow!("The input is empty"))?;
+    let transmission = line.trim();
+    let half_bytes = transmission.char_indices()
+        .map(|(j, c)| c.to_digit(16).map(|i| i as u8)
+            .ok_or_else(|| error_at(line_index, column_of(line, transmission) + j, format!("expected a hexadecimal digit, found '{}'", c))))
+        .collect::<anyhow::Result<Vec<u8>>>()?;
+    let bytes = half_bytes.chunks(2).map(|pair| pair[0] << 4 | pair.get(1).copied().unwrap_or(0)).collect::<Vec<u8>>();
+    parse_packets(&mut BitReader::new(&bytes, half_bytes.len() * 4))
+}
+
+/// Reads a transmission a few bits at a time, starting from the most significant bit of the first
+/// byte, without copying any of it.
+#[derive(Debug, Clone)]
+pub struct BitReader<'a> {
+    bytes: &'a [u8],
+    offset: usize,
+    end: usize,
+}
+
+impl<'a> BitReader<'a> {
+    /// A reader over the first `len` bits of `bytes`.
+    pub fn new(bytes: &'a [u8], len: usize) -> Self {
+        assert!(len <= bytes.len() * 8, "{} bits do not fit in {} bytes", len, bytes.len());
+        BitReader { bytes, offset: 0, end: len }
+    }
+
+    /// How many bits have been read since the start of the transmission.
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    pub fn remaining(&self) -> usize {
+        self.end - self.offset
+    }
+
+    fn bit(&self, index: usize) -> bool {
+        self.bytes[index / 8] >> (7 - index % 8) & 1 == 1
+    }
+
+    /// Whether every bit left is a zero, which is the padding at the end of a transmission.
+    pub fn is_padding(&self) -> bool {
+        (self.offset..self.end).all(|index| !self.bit(index))
+    }
+
+    /// Reads the next `n` bits as a binary number, most significant bit first, failing if the
+    /// transmission ends before then. `what` is the part of the packet being read, for the error.
+    pub fn read(&mut self, n: usize, what: &str) -> anyhow::Result<u64> {
+        assert!(n <= 64, "cannot read {} bits into a u64", n);
+        let bits = self.skip(n, what)?;
+        Ok(bits.fold(0, |acc, index| acc << 1 | self.bit(index) as u64))
+    }
+
+    /// Splits off a reader over the next `n` bits and moves past them.
+    pub fn take(&mut self, n: usize, what: &str) -> anyhow::Result<BitReader<'a>> {
+        let bits = self.skip(n, what)?;
+        Ok(BitReader { bytes: self.bytes, offset: bits.start, end: bits.end })
+    }
+
+    /// Moves past the next `n` bits, returning their indices.
+    fn skip(&mut self, n: usize, what: &str) -> anyhow::Result<std::ops::Range<usize>> {
+        if self.remaining() < n {
+            return Err(anyhow::anyhow!("The transmission ended while reading the {} of a packet ({} of {} bits left)", what, self.remaining(), n));
+        }
+        self.offset += n;
+        Ok(self.offset - n..self.offset)
     }
-    parse_packets(&bv)
 }
 
-fn parse_packets(bitstr: &BitSlice) -> Vec<Box<Packet>> {
+fn parse_packets(reader: &mut BitReader) -> anyhow::Result<Vec<Packet>> {
     let mut all_packets = vec![];
-    let mut remaining = BitVec::from_bitslice(bitstr);
+    while let Some(packet) = parse_packet(reader)? {
+        all_packets.push(packet);
+    }
+    Ok(all_packets)
+}
+
+/// Parses the packet at the reader's position and moves past it, or returns `None` if only padding
+/// is left.
+pub fn parse_packet(reader: &mut BitReader) -> anyhow::Result<Option<Packet>> {
+    if reader.is_padding() {
+        return Ok(None);
+    }
+    let version = reader.read(3, "version")? as u8;
+    let op = match Operator::from_type_id(reader.read(3, "type ID")?) {
+        Some(op) => op,
+        None => return parse_literal(reader).map(|value| Some(Packet::Literal { version, value })),
+    };
+
+    let children = if reader.read(1, "length type ID")? == 0 {
+        let length = reader.read(15, "subpacket length")? as usize;
+        parse_packets(&mut reader.take(length, "subpackets")?)?
+    } else {
+        let number_of_subpackets = reader.read(11, "number of subpackets")?;
+        (0..number_of_subpackets)
+            .map(|i| parse_packet(reader)?
+                .ok_or_else(|| anyhow::anyhow!("The transmission ended after {} of {} subpackets", i, number_of_subpackets)))
+            .collect::<anyhow::Result<Vec<Packet>>>()?
+    };
+    Ok(Some(Packet::Operator { version, op, children }))
+}
+
+/// Reads the groups of a literal value, each a continuation bit followed by 4 bits of the value.
+fn parse_literal(reader: &mut BitReader) -> anyhow::Result<u64> {
+    let mut value = 0;
+    let mut groups = 0;
     loop {
-        if let Some((next_packet, remaining_)) = parse_packet(&remaining) {
-            all_packets.push(Box::new(next_packet));
-            remaining = remaining_;
-        } else {
+        if reader.remaining() == 0 {
+            return Err(anyhow::anyhow!("The transmission ended while reading the literal value of a packet"));
+        }
+        let group = reader.read(5, "literal value")?;
+        groups += 1;
+        value = value << 4 | group & 0b1111;
+        if group >> 4 == 0 {
             break;
         }
     }
-    all_packets
-}
-
-fn parse_packet(bitstr: &BitSlice) -> Option<(Packet, BitVec)> {
-    if bitstr.is_empty() || bitstr.count_ones() == 0 {
-        return None;
-    }
-    let (version, remaining_after_version) = bitstr.split_at(3);
-    let (type_id, remaining_after_type_id) = remaining_after_version.split_at(3);
-    let mut remaining = BitVec::from_bitslice(remaining_after_type_id);
-    let mut subpackets = vec![];
-    let mut literal_value = None;
-
-    if type_id == bits![1, 0, 0] {
-        let mut chunks: Vec<BitVec> = vec![];
-        let mut skip_to_index = 0;
-        for chunk in remaining.chunks(5) {
-            let (not_last_chunk, value_chunk) = chunk.split_at(1);
-            chunks.push(BitVec::from_bitslice(value_chunk));
-            skip_to_index += 5;
-            if !not_last_chunk[0] {
-                break;
-            }
-        }
-        let (_, remaining_after_literal) = remaining.split_at(skip_to_index);
-        let mut literal = 0;
-        for (i, chunk) in chunks.iter().rev().enumerate() {
-            let chunk_as_value = ((chunk.load::<u8>().reverse_bits() >> 4) as u64) << (i * 4);
-            literal += chunk_as_value;
-        }
-        literal_value = Some(literal);
-        remaining = BitVec::from_bitslice(remaining_after_literal);
-    } else {
-        let (length_type_id, remaining_after_lti) = remaining.split_at(1);
-        if !length_type_id[0] { // 15 bits
-            let (length_in_bits, remaining_after_length) = remaining_after_lti.split_at(15);
-            let length: usize = (length_in_bits.load::<u16>().reverse_bits() >> 1) as usize;
-            let (subpackets_bitstr, remaining_after_subpackets) = remaining_after_length.split_at(length);
-            subpackets = parse_packets(subpackets_bitstr);
-            remaining = BitVec::from_bitslice(remaining_after_subpackets);
-        } else { // 11 bits
-            let (length_in_subpackets, remaining_after_length) = remaining_after_lti.split_at(11);
-            let number_of_subpackets: u16 = length_in_subpackets.load::<u16>().reverse_bits() >> 5;
-            let mut current_remaining = BitVec::from_bitslice(remaining_after_length);
-            for _ in 0..number_of_subpackets {
-                if let Some((subpacket, new_remaining)) = parse_packet(&current_remaining) {
-                    subpackets.push(Box::new(subpacket));
-                    current_remaining = new_remaining;
-                }
-            }
-            remaining = current_remaining;
-        }
+    if groups > 16 {
+        return Err(anyhow::anyhow!("A literal value of {} bits does not fit in 64 bits", groups * 4));
     }
-
-    Some((Packet::new(version, type_id, subpackets, literal_value), remaining))
+    Ok(value)
 }
 
-
 #[cfg(test)]
 mod tests {
     use crate::day_16::*;
@@ -206,8 +311,8 @@ mod tests {
     const TEST_INPUT_3: &str = "C0015000016115A2E0802F182340";
     const TEST_INPUT_4: &str = "A0016C880162017C3686B18A3D4780";
 
-    fn get_test_input(s: &str) -> Vec<String> {
-        s.split("\n").map(str::to_string).collect()
+    fn get_test_input(s: &str) -> Vec<Packet> {
+        parse_transmission(&s.split('\n').map(str::to_string).collect::<Vec<String>>()).unwrap()
     }
 
     #[test]
@@ -231,15 +336,68 @@ mod tests {
 
     #[test]
     fn test_part_2() {
-        assert_eq!(calc_packets(&get_test_input(TEST_INPUT_BASE)), 2021);
-        assert_eq!(calc_packets(&get_test_input(TEST_INPUT_5)), 3);
-        assert_eq!(calc_packets(&get_test_input(TEST_INPUT_6)), 54);
-        assert_eq!(calc_packets(&get_test_input(TEST_INPUT_7)), 7);
-        assert_eq!(calc_packets(&get_test_input(TEST_INPUT_8)), 9);
-        assert_eq!(calc_packets(&get_test_input(TEST_INPUT_9)), 1);
-        assert_eq!(calc_packets(&get_test_input(TEST_INPUT_10)), 0);
-        assert_eq!(calc_packets(&get_test_input(TEST_INPUT_11)), 0);
-        assert_eq!(calc_packets(&get_test_input(TEST_INPUT_12)), 1);
-        assert_eq!(calc_packets(&get_test_input(TEST_INPUT_13)), 0);
+        assert_eq!(calc_packets(&get_test_input(TEST_INPUT_BASE)).unwrap(), 2021);
+        assert_eq!(calc_packets(&get_test_input(TEST_INPUT_5)).unwrap(), 3);
+        assert_eq!(calc_packets(&get_test_input(TEST_INPUT_6)).unwrap(), 54);
+        assert_eq!(calc_packets(&get_test_input(TEST_INPUT_7)).unwrap(), 7);
+        assert_eq!(calc_packets(&get_test_input(TEST_INPUT_8)).unwrap(), 9);
+        assert_eq!(calc_packets(&get_test_input(TEST_INPUT_9)).unwrap(), 1);
+        assert_eq!(calc_packets(&get_test_input(TEST_INPUT_10)).unwrap(), 0);
+        assert_eq!(calc_packets(&get_test_input(TEST_INPUT_11)).unwrap(), 0);
+        assert_eq!(calc_packets(&get_test_input(TEST_INPUT_12)).unwrap(), 1);
+        assert_eq!(calc_packets(&get_test_input(TEST_INPUT_13)).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_packet_tree() {
+        assert_eq!(get_test_input(TEST_INPUT_BASE), [Packet::Literal { version: 6, value: 2021 }]);
+        assert_eq!(get_test_input("38006F45291200"), [Packet::Operator {
+            version: 1,
+            op: Operator::Lt,
+            children: vec![Packet::Literal { version: 6, value: 10 }, Packet::Literal { version: 2, value: 20 }],
+        }]);
+        assert_eq!(get_test_input(TEST_INPUT_1)[0].to_string(), "minimum (version 4)\n  minimum (version 1)\n    minimum (version 5)\n      15 (version 6)\n");
+    }
+
+    #[test]
+    fn test_eval_errors() {
+        let literal = |value| Packet::Literal { version: 0, value };
+        let operator = |op, children| Packet::Operator { version: 0, op, children };
+        assert_eq!(operator(Operator::Gt, vec![literal(1)]).eval().unwrap_err().to_string(), "Expected 'greater than' to compare 2 values, found 1");
+        assert_eq!(operator(Operator::Eq, vec![literal(1), literal(1), literal(1)]).eval().unwrap_err().to_string(), "Expected 'equal to' to compare 2 values, found 3");
+        assert_eq!(operator(Operator::Min, vec![]).eval().unwrap_err().to_string(), "Expected the minimum of at least 1 value, found none");
+        assert_eq!(operator(Operator::Sum, vec![literal(u64::MAX), literal(1)]).eval().unwrap_err().to_string(), format!("The sum of [{}, 1] does not fit in 64 bits", u64::MAX));
+        // Errors in subpackets are passed up.
+        assert!(operator(Operator::Product, vec![literal(2), operator(Operator::Max, vec![])]).eval().is_err());
+        assert_eq!(operator(Operator::Max, vec![literal(3), literal(7), literal(5)]).eval().unwrap(), 7);
+        assert_eq!(calc_packets(&[]).unwrap_err().to_string(), "The transmission has no packets");
+    }
+
+    #[test]
+    fn test_bit_reader() {
+        let bytes = [0b1011_0010, 0b0110_1111, 0b1000_0000];
+        let mut reader = BitReader::new(&bytes, 20);
+        assert_eq!(reader.read(3, "version").unwrap(), 0b101);
+        assert_eq!(reader.read(10, "version").unwrap(), 0b10010_01101);
+        assert_eq!(reader.offset(), 13);
+        let mut taken = reader.take(5, "subpackets").unwrap();
+        assert_eq!((taken.offset(), taken.remaining(), reader.offset()), (13, 5, 18));
+        assert_eq!(taken.read(5, "literal value").unwrap(), 0b11110);
+        assert!(taken.is_padding());
+        assert!(!BitReader::new(&bytes, 20).is_padding());
+        assert!(reader.is_padding());
+        assert_eq!(reader.read(3, "type ID").unwrap_err().to_string(), "The transmission ended while reading the type ID of a packet (2 of 3 bits left)");
+        assert_eq!(BitReader::new(&[0xFF; 9], 72).read(64, "version").unwrap(), u64::MAX);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let parse = |s: &str| parse_transmission(&s.split('\n').map(str::to_string).collect::<Vec<String>>()).err().unwrap().to_string();
+        assert_eq!(parse("\n8A004G801A8002F478"), "line 2, column 6: expected a hexadecimal digit, found 'G'");
+        assert_eq!(parse("D2FE"), "The transmission ended while reading the literal value of a packet");
+        assert_eq!(parse("D2FE2"), "The transmission ended while reading the literal value of a packet (4 of 5 bits left)");
+        assert_eq!(parse("38006F4529"), "The transmission ended while reading the subpackets of a packet (18 of 27 bits left)");
+        assert_eq!(parse(""), "The input is empty");
+        assert_eq!(parse("D3FFFFFFFFFFFFFFFFFFFFE0"), "A literal value of 72 bits does not fit in 64 bits");
     }
 }
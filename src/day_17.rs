@@ -1,253 +1,384 @@
-use std::collections::HashSet;
-
-pub fn part_1(lines: &Vec<String>) {
-    if let Some(first_line) = lines.iter().filter(|line| !line.trim().is_empty()).next() {
-        if let Some((min_x, max_x, min_y, max_y)) = parse_line(first_line) {
-            println!("Highest: {:?}", solve(min_x, max_x, min_y, max_y));
-        } else {
-            panic!("Could not parse line: {}", first_line);
-        }
-    } else {
-        panic!("No line with content provided!");
-    }
-}
-
-pub fn part_2(lines: &Vec<String>) {
-    println!("{:?}", lines);
-}
-
-
-fn parse_line(line: &str) -> Option<(i64, i64, i64, i64)> {
-    let (_, bounds_info) = line.split_at(13);
-    let whitespace_index = bounds_info.find(", ")?;
-    let (x_info, y_info) = bounds_info.split_at(whitespace_index);
-    let (_, x_bounds) = x_info.split_at(2);
-    let (_, y_bounds) = y_info.split_at(4);
-    let x_bounds_split = x_bounds.split("..");
-    let y_bounds_split = y_bounds.split("..");
-    let parsed: Vec<i64> = x_bounds_split.chain(y_bounds_split).filter_map(|bound| bound.parse::<i64>().ok()).collect();
-    let min_x = parsed.get(0)?;
-    let max_x = parsed.get(1)?;
-    let min_y = parsed.get(2)?;
-    let max_y = parsed.get(3)?;
-    Some((*min_x, *max_x, *min_y, *max_y))
-}
-
-//fn calc_sx(vx_0: i64, t: i64) -> i64 {
-//    if t < 0 {
-//        panic!("t cannot be negative!");
-//    }
-//    if vx_0 <= t { (vx_0 + (-1 * (t - 1)) / 2) * t } else { vx_0 * (vx_0 + 1) / 2 }
-//}
-//
-//fn calc_s(u: i64, t: i64, a: i64) -> i64 {
-//    (u * 2 + a * t) * t / 2
-//}
-//
-//fn calc_sy(vy_0: i64, t: i64) -> i64 {
-//    if t < 0 {
-//        panic!("t cannot be negative!");
-//    }
-//    (2 * vy_0 - t + 1) * t / 2
-//}
-//
-//fn find_highest_point(vy_0: i64) -> (i64, i64) {
-//    let mut sy = 0;
-//    let mut t = 0;
-//    for v in (0..=vy_0).rev() {
-//        t += 1;
-//        sy += v;
-//    }
-//    (sy, t)
-//}
-//
-//fn solve(min_x: i64, max_x: i64, min_y: i64, max_y: i64) -> Option<(i64, i64)> {
-//    let mut highest_y_found = None;
-//    let mut highest_x_found = None;
-//
-//    let mut vy_0 = 0;
-//    loop {
-//        let t_in_range_y = find_t_in_range(0, sy_highest, t_for_sy_highest, min_y, max_y, -1, false).into_iter().collect::<HashSet<i64>>();
-//        println!("{:?}", t_in_range_y);
-//        if t_in_range_y.is_empty() {
-//            break;
-//        }
-//
-//        let mut vx_0 = 0;
-//        loop {
-//            let t_in_range_x = find_t_in_range(vx_0, 0, 0, min_x, max_x, -1, true).into_iter().collect::<HashSet<i64>>();
-//            if t_in_range_x.is_empty() {
-//                break;
-//            }
-//
-//            let overlapping = t_in_range_x.intersection(&t_in_range_y).collect::<Vec<&i64>>();
-//            if let Some(t) = overlapping.first() {
-//                if vy_0 > highest_y_found.unwrap_or(0) {
-//                    highest_y_found = Some(vy_0);
-//                    highest_x_found = Some(vx_0);
-//                    println!("Found working: {} {} {}", vx_0, vy_0, t);
-//                    break;
-//                }
-//            }
-//            if highest_x_found.is_some() || vx_0 >= i64::MAX / 2 {
-//                break;
-//            }
-//            vx_0 += 1;
-//        }
-//
-//        if vy_0 == i64::MAX {
-//            break;
-//        }
-//        vy_0 += 1;
-//    }
-//
-//    highest_x_found.zip(highest_y_found)
-//}
-//
-//fn find_t_in_range(v_0: i64, s_0: i64, t_0: i64, min: i64, max: i64, is_x: bool) -> Vec<i64> {
-//    let distance_min = min - s_0;
-//    let distance_max = max - s_0;
-//    let a = -1;
-//    // t^2 - 2 v_0 t + s = 0
-//    // t =  v_0 +- (sqrt(4 (v_0^2 - s)) / 2)
-//    let min_exists = v_0 * v_0 > distance_min;
-//    let max_exists = v_0 * v_0 > distance_max;
-//    if is_x && min_exists && max_exists {
-//        let min_t = v_0 as f64 - ((4 * (v_0 * v_0 - distance_min)) as f64).sqrt() / 2.0;
-//        let max_t = v_0 as f64 - ((4 * (v_0 * v_0 - distance_max)) as f64).sqrt() / 2.0;
-//        ((min_t.round() as usize)..(max_t.round() as usize)).map(|i| i as i64).collect()
-//    } else if !is_x && min_exists && max_exists {
-//        let min_t = v_0 as f64 + ((4 * (v_0 * v_0 - distance_min)) as f64).sqrt() / 2.0;
-//        let max_t = v_0 as f64 + ((4 * (v_0 * v_0 - distance_max)) as f64).sqrt() / 2.0;
-//        ((min_t.round() as usize)..(max_t.round() as usize)).map(|i| i as i64).collect()
-//    } else {
-//        vec![]
-//    }
-//}
-
-fn solve(min_x: i64, max_x: i64, min_y: i64, max_y: i64) -> Option<(i64, i64)> {
-    let mut highest_vy_0 = -1;
-    let mut found = None;
-    let mut vy_0 = 0;
-    loop {
-        if let Some(vx_0) = vx0_if_vy0_valid(vy_0, min_x, max_x, min_y, max_y) {
-            if vy_0 > highest_vy_0 {
-                println!("Found {} {}", vx_0, vy_0);
-                highest_vy_0 = vy_0;
-                found = Some((vx_0, vy_0));
+use crate::input::PuzzleInput;
+use crate::registry::Example;
+use crate::solution::{Answer, Solution};
+use crate::utils::{column_of, error_at, parse_range, Grid};
+
+/// Day 17: Trick Shot
+/// https://adventofcode.com/2021/day/17
+pub struct Day17;
+
+impl Solution for Day17 {
+    type Input = Target;
+
+    fn parse(input: &PuzzleInput) -> anyhow::Result<Self::Input> {
+        let (i, first_line) = input.lines().iter().enumerate().find(|(_, line)| !line.trim().is_empty())
+            .ok_or_else(|| anyhow::anyhow!("No line with content provided!"))?;
+        parse_line(i, first_line)
+    }
+
+    fn part_1(target: &Self::Input) -> anyhow::Result<Answer> {
+        let highest = velocities(target)?.into_iter().map(|(_, vy_0)| highest_y(vy_0)).max()
+            .ok_or_else(|| anyhow::anyhow!("No initial velocity reaches the target area!"))?;
+        Ok(highest.into())
+    }
+
+    fn part_2(target: &Self::Input) -> anyhow::Result<Answer> {
+        Ok(velocities(target)?.len().into())
+    }
+}
+
+register_day! {
+    day: 17,
+    title: "Trick Shot",
+    solution: Day17,
+    parts: [1, 2],
+    examples: [
+        Example { input: "target area: x=20..30, y=-10..-5\n", part_1: Some("45"), part_2: Some("112") },
+    ],
+}
+
+
+/// The area the probe has to end a step in, inclusive at both ends.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Target {
+    pub min_x: i64,
+    pub max_x: i64,
+    pub min_y: i64,
+    pub max_y: i64,
+}
+
+impl Target {
+    pub fn contains(&self, (x, y): (i64, i64)) -> bool {
+        (self.min_x..=self.max_x).contains(&x) && (self.min_y..=self.max_y).contains(&y)
+    }
+}
+
+/// Parses the target area from a line such as `target area: x=20..30, y=-10..-5`.
+/// `line_index` is where the line is in the input, for reporting errors.
+pub fn parse_line(line_index: usize, line: &str) -> anyhow::Result<Target> {
+    let trimmed = line.trim();
+    let bounds_info = trimmed.strip_prefix("target area: ")
+        .ok_or_else(|| error_at(line_index, column_of(line, trimmed), "expected the line to start with 'target area: '"))?;
+    let (x_info, y_info) = bounds_info.split_once(", ")
+        .ok_or_else(|| error_at(line_index, column_of(line, bounds_info), "expected the x and y ranges to be separated by ', '"))?;
+    let (min_x, max_x) = parse_range(line_index, line, x_info, "x")?;
+    let (min_y, max_y) = parse_range(line_index, line, y_info, "y")?;
+    Ok(Target { min_x, max_x, min_y, max_y })
+}
+
+/// The nth triangular number, which is how far the probe goes along an axis in the `n` steps it
+/// takes for drag to stop it when it starts at speed `n`.
+fn triangular(n: i64) -> i64 {
+    n * (n + 1) / 2
+}
+
+/// The lowest speed at which the probe gets at least `distance` along the x axis before drag
+/// stops it.
+fn lowest_speed_reaching(distance: i64) -> i64 {
+    let mut speed = (2 * distance.max(0)).isqrt();
+    while speed > 0 && triangular(speed - 1) >= distance {
+        speed -= 1;
+    }
+    while triangular(speed) < distance {
+        speed += 1;
+    }
+    speed
+}
+
+/// The highest y the probe reaches when launched upwards at `vy_0`, which is where y stops
+/// increasing after `vy_0` steps.
+pub fn highest_y(vy_0: i64) -> i64 {
+    if vy_0 > 0 { triangular(vy_0) } else { 0 }
+}
+
+/// Where the probe goes when launched at `(vx_0, vy_0)`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Trajectory {
+    /// The probe's position after each step, starting from the launcher at step 0. This stops
+    /// once the probe is below the target and falling, as it can never come back up to it.
+    pub positions: Vec<(i64, i64)>,
+    /// The first step that ends inside the target, if any.
+    pub hit: Option<usize>,
+}
+
+impl Trajectory {
+    /// Draws the trajectory as in the puzzle description, with the launcher as `S`, the probe's
+    /// position after each step up to when it hits the target as `#`, and the target as `T`.
+    pub fn render(&self, target: &Target) -> Grid<char> {
+        let positions = &self.positions[..self.hit.map_or(self.positions.len(), |hit| hit + 1)];
+        let min_x = positions.iter().map(|(x, _)| *x).min().unwrap_or(0).min(target.min_x);
+        let max_x = positions.iter().map(|(x, _)| *x).max().unwrap_or(0).max(target.max_x);
+        let min_y = positions.iter().map(|(_, y)| *y).min().unwrap_or(0).min(target.min_y);
+        let max_y = positions.iter().map(|(_, y)| *y).max().unwrap_or(0).max(target.max_y);
+
+        // Rows go from the top down, so the highest y is row 0.
+        Grid::from_fn((max_y - min_y + 1) as usize, (max_x - min_x + 1) as usize, |(i, j)| {
+            let position = (min_x + j as i64, max_y - i as i64);
+            if position == (0, 0) {
+                'S'
+            } else if positions.contains(&position) {
+                '#'
+            } else if target.contains(position) {
+                'T'
+            } else {
+                '.'
             }
-        } else {
-            println!("No vx0 for vy0 of {}", vy_0);
-            break;
+        })
+    }
+}
+
+/// Steps the probe from the origin, recording where it goes and whether it hits the target.
+pub fn trajectory(vx_0: i64, vy_0: i64, target: &Target) -> Trajectory {
+    let (mut x, mut y, mut vx, mut vy) = (0, 0, vx_0, vy_0);
+    let mut positions = vec![(x, y)];
+    let mut hit = None;
+    while y >= target.min_y || vy > 0 {
+        x += vx;
+        y += vy;
+        vx -= vx.signum();
+        vy -= 1;
+        positions.push((x, y));
+        if hit.is_none() && target.contains((x, y)) {
+            hit = Some(positions.len() - 1);
         }
-        vy_0 += 1;
     }
-    found
+    Trajectory { positions, hit }
 }
 
-fn vx0_if_vy0_valid(vy_0: i64, min_x: i64, max_x: i64, min_y: i64, max_y: i64) -> Option<i64> {
-    let (min_t_y, max_t_y) = check_vy0(vy_0, min_y, max_y)?;
-    let mut vx_0 = 0;
-    // There's no point checking beyond when vx_0 is larger than max_x because that means that x
-    // will go immediately to a point beyond our range.
-    while vx_0 < max_x {
-        if let Some((min_t_x, max_t_x)) = check_vx0(vx_0, min_x, max_x) {
-            println!("Checking {} {} {} {}", min_t_y, max_t_y, min_t_x, max_t_x);
-            if (min_t_y..=max_t_y).collect::<HashSet<i64>>().intersection(&(min_t_x..=max_t_x).collect::<HashSet<i64>>()).count() > 0 {
-                return Some(vx_0);
-            }
+/// Whether the probe launched at `(vx_0, vy_0)` is ever inside the target area at the end of a
+/// step. This is the simplest possible model of the probe, which `velocities` is checked against.
+pub fn hits_target(vx_0: i64, vy_0: i64, target: &Target) -> bool {
+    trajectory(vx_0, vy_0, target).hit.is_some()
+}
+
+/// The steps after which the probe launched at `vx_0` is within the target's x range, as the
+/// first and last step, or no last step if drag stops it inside the range.
+/// x only ever moves towards where drag stops it, so this is a single run of steps.
+fn x_steps(vx_0: i64, target: &Target) -> Option<(i64, Option<i64>)> {
+    let (mut x, mut vx) = (0, vx_0);
+    let mut first = None;
+    for t in 1..=vx_0.abs() {
+        x += vx;
+        vx -= vx.signum();
+        let inside = (target.min_x..=target.max_x).contains(&x);
+        match (first, inside) {
+            (None, true) => first = Some(t),
+            (Some(first), false) => return Some((first, Some(t - 1))),
+            _ => {},
         }
-        vx_0 += 1;
-    }
-    None
-}
-
-/// Assuming vy_0 > 0, min_y and max_y are both < 0, and min_y <= max_y
-fn check_vy0(vy_0: i64, min_y: i64, max_y: i64) -> Option<(i64, i64)> {
-    let f64_max = f64::MAX as i64;
-    if vy_0 > f64_max || min_y > f64_max || max_y > f64_max ||
-       vy_0 < 0 || min_y > 0 || max_y > 0 || min_y > max_y {
-       panic!("Parameters do not fit assumptions! {} {} {}", vy_0, min_y, max_y);
-    }
-
-    // Because vy_0 > 0 and acceleration is -1, y goes up, then back down, and because both min_y
-    // and max_y are negative, y will hit max_y first, then min_y.
-    let min_t_y_pair = find_t_suvat(vy_0 as f64, max_y as f64, -1.0)?;
-    let max_t_y_pair = find_t_suvat(vy_0 as f64, min_y as f64, -1.0)?;
-    let min_t_y = if min_t_y_pair.0 >= 0.0 { min_t_y_pair.0 } else { min_t_y_pair.1 };
-    let max_t_y = if max_t_y_pair.0 >= 0.0 { max_t_y_pair.0 } else { max_t_y_pair.1 };
-
-    // Because we only check at integer values for t, the closest integer value to min_t and max_t
-    // will be the ones actually checked for. This also means that if upon rounding, all integer values
-    // from min_t_rounded .. max_t_rounded lie outside the range of min_t <= t <= max_t, we can
-    // return None as this means vy_0 is too high.
-    let min_t_y_rounded = min_t_y.round();
-    let max_t_y_rounded = max_t_y.round();
-    println!("Required {} {} {} {}", min_t_y, max_t_y, min_t_y_rounded, max_t_y_rounded);
-    if ((min_t_y_rounded as usize)..=(max_t_y_rounded as usize)).any(|t| min_t_y <= t as f64 && t as f64 <= max_t_y) {
-        Some((min_t_y_rounded as i64, max_t_y_rounded as i64))
+    }
+    // After |vx_0| steps the probe stops moving along x, where it stays forever.
+    if (target.min_x..=target.max_x).contains(&x) {
+        Some((first.unwrap_or(1), None))
     } else {
-        None
+        first.map(|first| (first, Some(vx_0.abs())))
     }
 }
 
-/// Assuming vx_0 > 0, min_x and max_x are both > 0, and min_x <= max_x
-fn check_vx0(vx_0: i64, min_x: i64, max_x: i64) -> Option<(i64, i64)> {
-    let f64_max = f64::MAX as i64;
-    if vx_0 > f64_max || min_x > f64_max || max_x > f64_max ||
-       vx_0 < 0 || min_x < 0 || max_x < 0 || min_x > max_x {
-       panic!("Parameters do not fit assumptions! {} {} {}", vx_0, min_x, max_x);
+/// The runs of steps after which the probe launched at `vy_0` is within the target's y range, as
+/// `(first, last)`. y rises and then falls, so it passes through the range at most twice.
+fn y_steps(vy_0: i64, target: &Target) -> Vec<(i64, i64)> {
+    let (mut t, mut y, mut vy) = (0, 0, vy_0);
+    // Going up at vy_0 the probe passes through the same heights on the way down, so it is at
+    // least vy_0 high for the first 2 * vy_0 steps. If that is above the target, skip straight to
+    // the end of those steps, where it is back at vy_0 and falling at the speed it started at.
+    if vy_0 > target.max_y && vy_0 > 0 {
+        t = 2 * vy_0;
+        y = vy_0;
+        vy = -vy_0;
     }
 
-    // In the case that vx_0 is actually enough to reach both min_x and max_x, the lowest t in both
-    // cases is taken because the quadratic formula will include the case that x continues
-    // decelerating and starts going backwards, which will not actually happen in our model.
-    let min_t_x_pair = find_t_suvat(vx_0 as f64, min_x as f64, -1.0)?;
-    let max_t_x_pair = find_t_suvat(vx_0 as f64, max_x as f64, -1.0)?;
-    let min_t_x = if min_t_x_pair.0 < min_t_x_pair.1 { min_t_x_pair.0 } else { min_t_x_pair.1 };
-    let max_t_x = if max_t_x_pair.0 < max_t_x_pair.1 { max_t_x_pair.0 } else { max_t_x_pair.1 };
-
-    let min_t_x_rounded = min_t_x.round();
-    let max_t_x_rounded = max_t_x.round();
-    if ((min_t_x_rounded as usize)..=(max_t_x_rounded as usize)).any(|t| min_t_x <= t as f64 && t as f64 <= max_t_x) {
-        Some((min_t_x_rounded as i64, max_t_x_rounded as i64))
-    } else {
-        None
+    let mut runs = vec![];
+    let mut first = None;
+    while y >= target.min_y || vy > 0 {
+        t += 1;
+        y += vy;
+        vy -= 1;
+        let inside = (target.min_y..=target.max_y).contains(&y);
+        match (first, inside) {
+            (None, true) => first = Some(t),
+            (Some(start), false) => {
+                runs.push((start, t - 1));
+                first = None;
+            },
+            _ => {},
+        }
+    }
+    if let Some(start) = first {
+        runs.push((start, t));
     }
+    runs
 }
 
-/// Rearranging the suvat equation in t, u, s, and a results in a quadratic.
-fn find_t_suvat(u: f64, s: f64, a: f64) -> Option<(f64, f64)> {
-    let determinant = u * u + 2.0 * a * s;
-    if determinant < 0.0 {
-        None
+/// The initial velocities worth trying, as `((lowest vx_0, highest vx_0), (lowest vy_0, highest vy_0))`.
+/// Fails if there are infinitely many initial velocities that hit the target, which happens when
+/// drag can stop the probe inside the target's x range and the y range includes 0, as then the
+/// probe can be launched as high as you like and still fall back through y = 0 inside the target.
+pub fn velocity_bounds(target: &Target) -> anyhow::Result<((i64, i64), (i64, i64))> {
+    // Any faster towards the target than its far side and the probe overshoots on the first step.
+    let vx_bounds = if target.min_x > 0 {
+        (lowest_speed_reaching(target.min_x), target.max_x)
+    } else if target.max_x < 0 {
+        (target.min_x, -lowest_speed_reaching(-target.max_x))
     } else {
-        Some(((-u + determinant.sqrt()) / a, (-u - determinant.sqrt()) / a))
-    }
+        (target.min_x, target.max_x)
+    };
+
+    let lowest_vy_0 = target.min_y.min(0);
+    let highest_vy_0 = if target.max_y < 0 {
+        // On the way back down the probe goes from y = 0 to -(vy_0 + 1) in one step.
+        -target.min_y - 1
+    } else if target.min_y > 0 {
+        target.max_y
+    } else {
+        let stops_inside = (vx_bounds.0..=vx_bounds.1)
+            .map(|vx_0| vx_0.signum() * triangular(vx_0.abs()))
+            .find(|x| (target.min_x..=target.max_x).contains(x));
+        if let Some(x) = stops_inside {
+            return Err(anyhow::anyhow!(
+                "Infinitely many initial velocities hit the target area, as the probe can stop at x = {} and fall through y = 0 inside it", x));
+        }
+        // Above max_y, the probe is only inside the target as it falls back through y = 0 after
+        // 2 * vy_0 + 1 steps, by which time x has left the target for good.
+        target.max_y.max(target.min_x.abs()).max(target.max_x.abs())
+    };
+
+    Ok((vx_bounds, (lowest_vy_0, highest_vy_0)))
 }
 
-//fn find_t_triangular(u: f64, s: f64, is_x: bool) -> Option<(f64, f64)> {
-//    None
-//}
+/// Every initial velocity `(vx_0, vy_0)` that puts the probe inside the target area after some
+/// step, in order of `vx_0` and then `vy_0`. The target can be anywhere relative to the launcher.
+pub fn velocities(target: &Target) -> anyhow::Result<Vec<(i64, i64)>> {
+    let ((min_vx_0, max_vx_0), (min_vy_0, max_vy_0)) = velocity_bounds(target)?;
+    let y_runs = (min_vy_0..=max_vy_0).map(|vy_0| (vy_0, y_steps(vy_0, target))).collect::<Vec<(i64, Vec<(i64, i64)>)>>();
 
+    let mut found = vec![];
+    for vx_0 in min_vx_0..=max_vx_0 {
+        let (x_first, x_last) = match x_steps(vx_0, target) {
+            Some(steps) => steps,
+            None => continue,
+        };
+        for (vy_0, runs) in y_runs.iter() {
+            let overlaps = runs.iter().any(|&(y_first, y_last)| y_first.max(x_first) <= x_last.map_or(y_last, |x_last| y_last.min(x_last)));
+            if overlaps {
+                found.push((vx_0, *vy_0));
+            }
+        }
+    }
+    Ok(found)
+}
 
 #[cfg(test)]
 mod tests {
-    //use crate::day_17::*;
+    use crate::day_17::*;
 
-    const TEST_INPUT_1: &str = r#"
-"#;
+    const TEST_INPUT_1: &str = "target area: x=20..30, y=-10..-5";
 
-    fn get_test_input(s: &str) -> Vec<String> {
-        s.split("\n").map(str::to_string).collect()
+    fn get_test_input(s: &str) -> Target {
+        parse_line(0, s).unwrap()
+    }
+
+    fn brute_force(target: &Target) -> Vec<(i64, i64)> {
+        (-100..=100).flat_map(|vx_0| (-100..=100).map(move |vy_0| (vx_0, vy_0)))
+            .filter(|&(vx_0, vy_0)| hits_target(vx_0, vy_0, target))
+            .collect()
     }
 
     #[test]
     fn test_part_1() {
-        get_test_input(TEST_INPUT_1);
+        let target = get_test_input(TEST_INPUT_1);
+        assert_eq!(velocities(&target).unwrap().iter().map(|(_, vy_0)| *vy_0).max(), Some(9));
+        assert_eq!(highest_y(9), 45);
     }
 
     #[test]
     fn test_part_2() {
+        let target = get_test_input(TEST_INPUT_1);
+        assert!(hits_target(7, 2, &target));
+        assert!(hits_target(6, 9, &target));
+        assert!(!hits_target(17, -4, &target));
+        assert_eq!(velocities(&target).unwrap().len(), 112);
+    }
+
+    #[test]
+    fn test_trajectory() {
+        let target = get_test_input(TEST_INPUT_1);
+        let shot = trajectory(7, 2, &target);
+        assert_eq!(shot.hit, Some(7));
+        assert_eq!(&shot.positions[..4], [(0, 0), (7, 2), (13, 3), (18, 3)]);
+        assert_eq!(shot.render(&target).to_string(), r#".............#....#............
+.......#..............#........
+...............................
+S........................#.....
+...............................
+...............................
+...........................#...
+...............................
+....................TTTTTTTTTTT
+....................TTTTTTTTTTT
+....................TTTTTTTT#TT
+....................TTTTTTTTTTT
+....................TTTTTTTTTTT
+....................TTTTTTTTTTT"#);
+
+        let shot = trajectory(6, 3, &target);
+        assert_eq!(shot.hit, Some(9));
+        assert_eq!(shot.render(&target).to_string(), r#"...............#..#............
+...........#........#..........
+...............................
+......#..............#.........
+...............................
+...............................
+S....................#.........
+...............................
+...............................
+...............................
+.....................#.........
+....................TTTTTTTTTTT
+....................TTTTTTTTTTT
+....................TTTTTTTTTTT
+....................TTTTTTTTTTT
+....................T#TTTTTTTTT
+....................TTTTTTTTTTT"#);
+
+        let miss = trajectory(17, -4, &target);
+        assert_eq!(miss.hit, None);
+        assert_eq!(miss.positions, [(0, 0), (17, -4), (33, -9), (48, -15)]);
+    }
+
+    #[test]
+    fn test_any_quadrant() {
+        let targets = [
+            "target area: x=20..30, y=-10..-5",
+            "target area: x=-30..-20, y=-10..-5",
+            "target area: x=20..30, y=5..10",
+            "target area: x=-30..-20, y=5..10",
+            "target area: x=-5..5, y=-10..-5",
+            "target area: x=-3..4, y=2..6",
+            "target area: x=22..27, y=-5..5",
+            "target area: x=-2..2, y=-4..-1",
+            "target area: x=1..1, y=1..1",
+        ];
+        for s in targets {
+            let target = get_test_input(s);
+            assert_eq!(velocities(&target).unwrap(), brute_force(&target), "{}", s);
+        }
+    }
+
+    #[test]
+    fn test_infinitely_many() {
+        assert!(velocities(&get_test_input("target area: x=20..30, y=-5..5")).is_err());
+        assert!(velocities(&get_test_input("target area: x=-1..1, y=0..0")).is_err());
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let parse = |s: &str| parse_line(0, s).unwrap_err().to_string();
+        assert_eq!(parse("target: x=20..30, y=-10..-5"), "line 1, column 1: expected the line to start with 'target area: '");
+        assert_eq!(parse("target area: x=20..30 y=-10..-5"), "line 1, column 14: expected the x and y ranges to be separated by ', '");
+        assert_eq!(parse("target area: y=20..30, x=-10..-5"), "line 1, column 14: expected the range to start with 'x='");
+        assert_eq!(parse("target area: x=20..30, y=-10..-5a"), "line 1, column 31: could not parse '-5a': invalid digit found in string");
+        assert_eq!(parse("target area: x=20-30, y=-10..-5"), "line 1, column 16: expected the bounds to be separated by '..'");
+        assert_eq!(parse("target area: x=30..20, y=-10..-5"), "line 1, column 16: the x range is empty");
     }
 }
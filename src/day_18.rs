@@ -1,24 +1,73 @@
-pub fn part_1(lines: &Vec<String>) {
-    println!("Magnitude of Sum: {}", magnitude_of_sum(lines));
+use crate::input::PuzzleInput;
+use crate::registry::Example;
+use crate::solution::{Answer, Solution};
+use crate::utils::{column_of, error_at};
+
+
+/// Day 18: Snailfish
+/// https://adventofcode.com/2021/day/18
+pub struct Day18;
+
+impl Solution for Day18 {
+    type Input = Vec<Vec<Component>>;
+
+    fn parse(input: &PuzzleInput) -> anyhow::Result<Self::Input> {
+        parse_lines(input.lines())
+    }
+
+    fn part_1(numbers: &Self::Input) -> anyhow::Result<Answer> {
+        Ok(magnitude_of_sum(numbers).into())
+    }
+
+    fn part_2(numbers: &Self::Input) -> anyhow::Result<Answer> {
+        Ok(largest_magnitude_of_any_two(numbers).into())
+    }
 }
 
-pub fn part_2(lines: &Vec<String>) {
-    println!("Largest magnitude from any two of above: {}", largest_magnitude_of_any_two(lines));
+register_day! {
+    day: 18,
+    title: "Snailfish",
+    solution: Day18,
+    parts: [1, 2],
+    examples: [
+        Example {
+            input: r#"[[[0,[5,8]],[[1,7],[9,6]]],[[4,[1,2]],[[1,4],2]]]
+[[[5,[2,8]],4],[5,[[9,9],0]]]
+[6,[[[6,2],[5,6]],[[7,6],[4,7]]]]
+[[[6,[0,7]],[0,9]],[4,[9,[9,0]]]]
+[[[7,[6,4]],[3,[1,3]]],[[[5,5],1],9]]
+[[6,[[7,3],[3,2]]],[[[3,8],[5,7]],4]]
+[[[[5,4],[7,7]],8],[[8,3],8]]
+[[9,3],[[9,9],[6,[4,9]]]]
+[[2,[[7,7],7]],[[5,8],[[9,3],[0,2]]]]
+[[[[5,2],5],[8,[3,7]]],[[5,[7,5]],[4,4]]]
+"#,
+            part_1: Some("4140"),
+            part_2: Some("3993"),
+        },
+    ],
+}
+
+fn parse_lines(lines: &[String]) -> anyhow::Result<Vec<Vec<Component>>> {
+    let numbers = lines.iter().enumerate().filter(|(_, line)| !line.trim().is_empty()).map(|(i, line)| {
+        parse_line(i, line)
+    }).collect::<anyhow::Result<Vec<Vec<Component>>>>()?;
+    if numbers.is_empty() {
+        return Err(anyhow::anyhow!("The input is empty"));
+    }
+    Ok(numbers)
 }
 
-fn magnitude_of_sum(lines: &Vec<String>) -> i64 {
-    let sum = lines.iter().filter(|line| !line.trim().is_empty()).map(|line| {
-        parse(line)
-    }).reduce(|a, b| {
+/// Adds up every snailfish number in order and returns the magnitude of the sum.
+pub fn magnitude_of_sum(numbers: &[Vec<Component>]) -> i64 {
+    let sum = numbers.iter().cloned().reduce(|a, b| {
         add(&a, &b)
     }).unwrap();
     magnitude(&sum)
 }
 
-fn largest_magnitude_of_any_two(lines: &Vec<String>) -> i64 {
-    let parsed_lines = lines.iter().filter(|line| !line.trim().is_empty()).map(|line| {
-        parse(line)
-    }).collect::<Vec<Vec<Component>>>();
+/// Finds the largest magnitude of the sum of any two different snailfish numbers.
+pub fn largest_magnitude_of_any_two(parsed_lines: &[Vec<Component>]) -> i64 {
     let mut largest_magnitude = 0;
 
     for i in 0..parsed_lines.len() {
@@ -37,7 +86,7 @@ fn largest_magnitude_of_any_two(lines: &Vec<String>) -> i64 {
 
 
 #[derive(Clone, PartialEq)]
-enum Component {
+pub enum Component {
     Open,
     Close,
     Separator,
@@ -71,7 +120,7 @@ struct ExplodingPair {
 
 impl ExplodingPair {
     fn new(left_value: Option<(usize, i64)>, right_value: Option<(usize, i64)>, pair: (i64, i64), index: usize) -> Self {
-        Self { left_value: left_value, right_value: right_value, pair: pair, index: index }
+        Self { left_value, right_value, pair, index }
     }
 }
 
@@ -101,36 +150,65 @@ impl ExplodingPairBuilder {
 }
 
 
-fn parse(snailfish_number: &str) -> Vec<Component> {
-    let digits = "0123456789";
-    let mut number_builder = String::new();
-    let mut new_number: Vec<Component> = Vec::new();
+/// Parses one snailfish number into its components.
+pub fn parse(snailfish_number: &str) -> anyhow::Result<Vec<Component>> {
+    parse_line(0, snailfish_number)
+}
 
-    for c in snailfish_number.chars() {
-        if digits.contains(c) {
-            number_builder.push(c);
-        } else {
-            if !number_builder.is_empty() {
-                let number = number_builder.parse::<i64>().unwrap(); // panic here if number can't be parsed
-                new_number.push(Component::Number(number));
+/// Parses the snailfish number on line `line_index` of the input.
+fn parse_line(line_index: usize, line: &str) -> anyhow::Result<Vec<Component>> {
+    let trimmed = line.trim();
+    let start = column_of(line, trimmed);
+    let chars = trimmed.char_indices().map(|(j, c)| (start + j, c)).collect::<Vec<(usize, char)>>();
+    let mut position = 0;
+    let mut new_number: Vec<Component> = Vec::new();
+    parse_element(line_index, &chars, &mut position, &mut new_number)?;
+    if let Some((column, c)) = chars.get(position) {
+        return Err(error_at(line_index, *column, format!("unexpected '{}' after the end of the number", c)));
+    }
+    Ok(new_number)
+}
 
-                number_builder = String::new();
+/// Parses either a regular number or a pair starting at `chars[*position]`, moving `position` past it.
+fn parse_element(line_index: usize, chars: &[(usize, char)], position: &mut usize, new_number: &mut Vec<Component>) -> anyhow::Result<()> {
+    let end_column = chars.last().map_or(0, |(column, _)| column + 1);
+    let expect = |position: &mut usize, expected: char| match chars.get(*position) {
+        Some((_, c)) if *c == expected => {
+            *position += 1;
+            Ok(())
+        },
+        Some((column, c)) => Err(error_at(line_index, *column, format!("expected '{}', found '{}'", expected, c))),
+        None => Err(error_at(line_index, end_column, format!("expected '{}', found the end of the line", expected))),
+    };
+
+    match chars.get(*position) {
+        Some((_, '[')) => {
+            *position += 1;
+            new_number.push(Component::Open);
+            parse_element(line_index, chars, position, new_number)?;
+            expect(position, ',')?;
+            new_number.push(Component::Separator);
+            parse_element(line_index, chars, position, new_number)?;
+            expect(position, ']')?;
+            new_number.push(Component::Close);
+        },
+        Some((column, c)) if c.is_ascii_digit() => {
+            let mut value: i64 = 0;
+            while let Some((_, c)) = chars.get(*position).filter(|(_, c)| c.is_ascii_digit()) {
+                value = value.checked_mul(10).and_then(|value| value.checked_add(c.to_digit(10).unwrap() as i64))
+                    .ok_or_else(|| error_at(line_index, *column, "the number is too large"))?;
+                *position += 1;
             }
-
-            let component = match c {
-                '[' => Component::Open,
-                ']' => Component::Close,
-                ',' => Component::Separator,
-                _ => panic!("Unknown character found! {}", c),
-            };
-            new_number.push(component);
-        }
+            new_number.push(Component::Number(value));
+        },
+        Some((column, c)) => return Err(error_at(line_index, *column, format!("expected '[' or a number, found '{}'", c))),
+        None => return Err(error_at(line_index, end_column, "expected '[' or a number, found the end of the line")),
     }
-
-    new_number
+    Ok(())
 }
 
-fn magnitude(snailfish_number: &Vec<Component>) -> i64 {
+/// Calculates the magnitude of a snailfish number, 3 times that of its left element plus 2 times that of its right element.
+pub fn magnitude(snailfish_number: &[Component]) -> i64 {
     if snailfish_number.len() == 1 {
         if let Some(Component::Number(value)) = snailfish_number.first() {
             return *value;
@@ -142,7 +220,7 @@ fn magnitude(snailfish_number: &Vec<Component>) -> i64 {
     let mut left: Vec<Component> = Vec::new();
     let mut right: Vec<Component> = Vec::new();
 
-    for component in snailfish_number[1..snailfish_number.len() - 1].into_iter() {
+    for component in snailfish_number[1..snailfish_number.len() - 1].iter() {
         if found_sep {
             right.push(component.clone());
         } else {
@@ -173,7 +251,8 @@ fn magnitude(snailfish_number: &Vec<Component>) -> i64 {
     left_magnitude + right_magnitude
 }
 
-fn add(number1: &Vec<Component>, number2: &Vec<Component>) -> Vec<Component> {
+/// Adds two snailfish numbers together, reducing the result.
+pub fn add(number1: &[Component], number2: &[Component]) -> Vec<Component> {
     let mut result = vec![Component::Open];
     result.append(&mut number1.to_vec());
     result.push(Component::Separator);
@@ -182,15 +261,17 @@ fn add(number1: &Vec<Component>, number2: &Vec<Component>) -> Vec<Component> {
     reduce(&result)
 }
 
-fn reduce(snailfish_number: &Vec<Component>) -> Vec<Component> {
+/// Explodes and splits a snailfish number until neither applies.
+pub fn reduce(snailfish_number: &[Component]) -> Vec<Component> {
     let mut current_number = snailfish_number.to_vec();
-    while let Some(reduction) = try_explode(&current_number).or(try_split(&current_number)).or(None) {
+    while let Some(reduction) = try_explode(&current_number).or_else(|| try_split(&current_number)) {
         current_number = reduction;
     }
     current_number
 }
 
-fn try_explode(snailfish_number: &Vec<Component>) -> Option<Vec<Component>> {
+/// Explodes the leftmost pair nested inside four pairs, if there is one.
+pub fn try_explode(snailfish_number: &[Component]) -> Option<Vec<Component>> {
     let mut nesting = 0;
     let mut builder: ExplodingPairBuilder = ExplodingPairBuilder::new();
 
@@ -234,7 +315,7 @@ fn try_explode(snailfish_number: &Vec<Component>) -> Option<Vec<Component>> {
                             // Pair found already
                         },
                         Some(_) => {
-                            builder.left_value = builder.number_window.last().map(|t| t.clone());
+                            builder.left_value = builder.number_window.last().copied();
                             builder.pair = Some((None, None));
                             builder.index = Some(i);
                         },
@@ -268,7 +349,8 @@ fn try_explode(snailfish_number: &Vec<Component>) -> Option<Vec<Component>> {
     Some(new_number)
 }
 
-fn try_split(snailfish_number: &Vec<Component>) -> Option<Vec<Component>> {
+/// Splits the leftmost regular number that is 10 or greater, if there is one.
+pub fn try_split(snailfish_number: &[Component]) -> Option<Vec<Component>> {
     let (i, value) = snailfish_number.iter().enumerate().find_map(|(i, component)| {
         match component {
             Component::Number(value) if *value >= 10 => Some((i, *value)),
@@ -300,13 +382,13 @@ mod tests {
 [[[[5,2],5],[8,[3,7]]],[[5,[7,5]],[4,4]]]
 "#;
 
-    fn get_test_input(s: &str) -> Vec<String> {
-        s.split("\n").map(str::to_string).collect()
+    fn get_test_input(s: &str) -> Vec<Vec<Component>> {
+        parse_lines(&s.split('\n').map(str::to_string).collect::<Vec<String>>()).unwrap()
     }
 
     #[test]
     fn test_parse() {
-        assert_eq!(parse("[9,8]"), vec![Component::Open, Component::Number(9), Component::Separator, Component::Number(8), Component::Close]);
+        assert_eq!(parse("[9,8]").unwrap(), vec![Component::Open, Component::Number(9), Component::Separator, Component::Number(8), Component::Close]);
     }
 
     #[test]
@@ -320,21 +402,21 @@ mod tests {
             ("[[[[8,7],[7,7]],[[8,6],[7,7]]],[[[0,7],[6,6]],[8,7]]]", 3488),
         ];
         for (input, expected) in test_cases {
-            assert_eq!(magnitude(&parse(input)), expected);
+            assert_eq!(magnitude(&parse(input).unwrap()), expected);
         }
     }
 
     #[test]
     fn test_add() {
-        assert_eq!(add(&parse("[1,2]"), &parse("[[3,4],5]")), parse("[[1,2],[[3,4],5]]"));
-        assert_eq!(add(&parse("[[[[4,3],4],4],[7,[[8,4],9]]]"), &parse("[1,1]")), parse("[[[[0,7],4],[[7,8],[6,0]]],[8,1]]"));
-        assert_eq!(add(&parse("[[[0,[4,5]],[0,0]],[[[4,5],[2,6]],[9,5]]]"), &parse("[7,[[[3,7],[4,3]],[[6,3],[8,8]]]]")), parse("[[[[4,0],[5,4]],[[7,7],[6,0]]],[[8,[7,7]],[[7,9],[5,0]]]]"));
+        assert_eq!(add(&parse("[1,2]").unwrap(), &parse("[[3,4],5]").unwrap()), parse("[[1,2],[[3,4],5]]").unwrap());
+        assert_eq!(add(&parse("[[[[4,3],4],4],[7,[[8,4],9]]]").unwrap(), &parse("[1,1]").unwrap()), parse("[[[[0,7],4],[[7,8],[6,0]]],[8,1]]").unwrap());
+        assert_eq!(add(&parse("[[[0,[4,5]],[0,0]],[[[4,5],[2,6]],[9,5]]]").unwrap(), &parse("[7,[[[3,7],[4,3]],[[6,3],[8,8]]]]").unwrap()), parse("[[[[4,0],[5,4]],[[7,7],[6,0]]],[[8,[7,7]],[[7,9],[5,0]]]]").unwrap());
     }
 
     #[test]
     fn test_reduce() {
-        assert_eq!(reduce(&parse("[[[[[4,3],4],4],[7,[[8,4],9]]],[1,1]]")), parse("[[[[0,7],4],[[7,8],[6,0]]],[8,1]]"));
-        assert_eq!(reduce(&parse("[[[[0,[4,5]],[0,0]],[[[4,5],[2,6]],[9,5]]],[7,[[[3,7],[4,3]],[[6,3],[8,8]]]]]")), parse("[[[[4,0],[5,4]],[[7,7],[6,0]]],[[8,[7,7]],[[7,9],[5,0]]]]"));
+        assert_eq!(reduce(&parse("[[[[[4,3],4],4],[7,[[8,4],9]]],[1,1]]").unwrap()), parse("[[[[0,7],4],[[7,8],[6,0]]],[8,1]]").unwrap());
+        assert_eq!(reduce(&parse("[[[[0,[4,5]],[0,0]],[[[4,5],[2,6]],[9,5]]],[7,[[[3,7],[4,3]],[[6,3],[8,8]]]]]").unwrap()), parse("[[[[4,0],[5,4]],[[7,7],[6,0]]],[[8,[7,7]],[[7,9],[5,0]]]]").unwrap());
     }
 
     #[test]
@@ -347,9 +429,9 @@ mod tests {
             ("[[3,[2,[8,0]]],[9,[5,[4,[3,2]]]]]", "[[3,[2,[8,0]]],[9,[5,[7,0]]]]"),
         ];
         for (input, expected) in test_cases {
-            assert_eq!(try_explode(&parse(input)), Some(parse(expected)));
+            assert_eq!(try_explode(&parse(input).unwrap()), Some(parse(expected).unwrap()));
         }
-        assert_eq!(try_explode(&parse("[[[[0,7],4],[15,[0,13]]],[1,1]]")), None);
+        assert_eq!(try_explode(&parse("[[[[0,7],4],[15,[0,13]]],[1,1]]").unwrap()), None);
     }
 
     #[test]
@@ -360,9 +442,9 @@ mod tests {
             ("[[[[0,7],4],[14,[0,13]]],[1,1]]", "[[[[0,7],4],[[7,7],[0,13]]],[1,1]]"),
         ];
         for (input, expected) in test_cases {
-            assert_eq!(try_split(&parse(input)), Some(parse(expected)));
+            assert_eq!(try_split(&parse(input).unwrap()), Some(parse(expected).unwrap()));
         }
-        assert_eq!(try_split(&parse("[[[[0,7],4],[[7,8],[6,0]]],[8,1]]")), None);
+        assert_eq!(try_split(&parse("[[[[0,7],4],[[7,8],[6,0]]],[8,1]]").unwrap()), None);
     }
 
     #[test]
@@ -386,4 +468,14 @@ mod tests {
     fn test_largest_magnitude_of_any_two() {
         assert_eq!(largest_magnitude_of_any_two(&get_test_input(TEST_INPUT_EXAMPLE)), 3993);
     }
+
+    #[test]
+    fn test_parse_errors() {
+        let parse_error = |s: &str| parse_lines(&s.split('\n').map(str::to_string).collect::<Vec<String>>()).unwrap_err().to_string();
+        assert_eq!(parse_error("[1,2]\n[[1,2],x]"), "line 2, column 8: expected '[' or a number, found 'x'");
+        assert_eq!(parse_error("[[1,2],3"), "line 1, column 9: expected ']', found the end of the line");
+        assert_eq!(parse_error("[1;2]"), "line 1, column 3: expected ',', found ';'");
+        assert_eq!(parse_error("[1,2]]"), "line 1, column 6: unexpected ']' after the end of the number");
+        assert_eq!(parse_error(""), "The input is empty");
+    }
 }
@@ -0,0 +1,465 @@
+use std::collections::HashSet;
+use std::ops::{Add, Sub};
+use crate::input::{PuzzleInput, Section};
+use crate::registry::Example;
+use crate::solution::{Answer, Solution};
+use crate::utils::{column_of, error_at, parse_at};
+
+
+/// Day 19: Beacon Scanner
+/// https://adventofcode.com/2021/day/19
+pub struct Day19;
+
+impl Solution for Day19 {
+    type Input = Vec<Vec<Point>>;
+
+    fn parse(input: &PuzzleInput) -> anyhow::Result<Self::Input> {
+        parse_reports(input)
+    }
+
+    fn part_1(reports: &Self::Input) -> anyhow::Result<Answer> {
+        Ok(assemble_map(reports)?.beacons.len().into())
+    }
+
+    fn part_2(reports: &Self::Input) -> anyhow::Result<Answer> {
+        Ok(assemble_map(reports)?.largest_scanner_distance().into())
+    }
+}
+
+register_day! {
+    day: 19,
+    title: "Beacon Scanner",
+    solution: Day19,
+    parts: [1, 2],
+    examples: [
+        Example {
+            input: r#"--- scanner 0 ---
+404,-588,-901
+528,-643,409
+-838,591,734
+390,-675,-793
+-537,-823,-458
+-485,-357,347
+-345,-311,381
+-661,-816,-575
+-876,649,763
+-618,-824,-621
+553,345,-567
+474,580,667
+-447,-329,318
+-584,868,-557
+544,-627,-890
+564,392,-477
+455,729,728
+-892,524,684
+-689,845,-530
+423,-701,434
+7,-33,-71
+630,319,-379
+443,580,662
+-789,900,-551
+459,-707,401
+
+--- scanner 1 ---
+686,422,578
+605,423,415
+515,917,-361
+-336,658,858
+95,138,22
+-476,619,847
+-340,-569,-846
+567,-361,727
+-460,603,-452
+669,-402,600
+729,430,532
+-500,-761,534
+-322,571,750
+-466,-666,-811
+-429,-592,574
+-355,545,-477
+703,-491,-529
+-328,-685,520
+413,935,-424
+-391,539,-444
+586,-435,557
+-364,-763,-893
+807,-499,-711
+755,-354,-619
+553,889,-390
+
+--- scanner 2 ---
+649,640,665
+682,-795,504
+-784,533,-524
+-644,584,-595
+-588,-843,648
+-30,6,44
+-674,560,763
+500,723,-460
+609,671,-379
+-555,-800,653
+-675,-892,-343
+697,-426,-610
+578,704,681
+493,664,-388
+-671,-858,530
+-667,343,800
+571,-461,-707
+-138,-166,112
+-889,563,-600
+646,-828,498
+640,759,510
+-630,509,768
+-681,-892,-333
+673,-379,-804
+-742,-814,-386
+577,-820,562
+
+--- scanner 3 ---
+-589,542,597
+605,-692,669
+-500,565,-823
+-660,373,557
+-458,-679,-417
+-488,449,543
+-626,468,-788
+338,-750,-386
+528,-832,-391
+562,-778,733
+-938,-730,414
+543,643,-506
+-524,371,-870
+407,773,750
+-104,29,83
+378,-903,-323
+-778,-728,485
+426,699,580
+-438,-605,-362
+-469,-447,-387
+509,732,623
+647,635,-688
+-868,-804,481
+614,-800,639
+595,780,-596
+
+--- scanner 4 ---
+727,592,562
+-293,-554,779
+441,611,-461
+-714,465,-776
+-743,427,-804
+-660,-479,-426
+832,-632,460
+927,-485,-438
+408,393,-506
+466,436,-512
+110,16,151
+-258,-428,682
+-393,719,612
+-211,-452,876
+808,-476,-593
+-575,615,604
+-485,667,467
+-680,325,-822
+-627,-443,-432
+872,-547,-609
+833,512,582
+807,604,487
+839,-516,451
+891,-625,532
+-652,-548,-490
+30,-46,-14
+"#,
+            part_1: Some("79"),
+            part_2: Some("3621"),
+        },
+    ],
+}
+
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Point {
+    pub x: i64,
+    pub y: i64,
+    pub z: i64,
+}
+
+impl Point {
+    pub fn new(x: i64, y: i64, z: i64) -> Self {
+        Point { x, y, z }
+    }
+
+    pub fn manhattan_distance(&self, other: &Point) -> i64 {
+        (self.x - other.x).abs() + (self.y - other.y).abs() + (self.z - other.z).abs()
+    }
+
+    /// The square of the straight line distance, which stays the same however the scanners are
+    /// turned, and is exact as it needs no square root.
+    pub fn squared_distance(&self, other: &Point) -> i64 {
+        let difference = *self - *other;
+        difference.x * difference.x + difference.y * difference.y + difference.z * difference.z
+    }
+}
+
+impl Add for Point {
+    type Output = Point;
+
+    fn add(self, other: Point) -> Point {
+        Point::new(self.x + other.x, self.y + other.y, self.z + other.z)
+    }
+}
+
+impl Sub for Point {
+    type Output = Point;
+
+    fn sub(self, other: Point) -> Point {
+        Point::new(self.x - other.x, self.y - other.y, self.z - other.z)
+    }
+}
+
+/// A rotation by a multiple of 90 degrees around each axis, as a matrix.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Rotation([[i64; 3]; 3]);
+
+impl Rotation {
+    pub const IDENTITY: Rotation = Rotation([[1, 0, 0], [0, 1, 0], [0, 0, 1]]);
+
+    pub fn apply(&self, point: Point) -> Point {
+        let [a, b, c] = self.0;
+        let row = |[i, j, k]: [i64; 3]| i * point.x + j * point.y + k * point.z;
+        Point::new(row(a), row(b), row(c))
+    }
+
+    fn determinant(&self) -> i64 {
+        let [[a, b, c], [d, e, f], [g, h, i]] = self.0;
+        a * (e * i - f * h) - b * (d * i - f * g) + c * (d * h - e * g)
+    }
+}
+
+/// The 24 ways a scanner can be facing: every matrix that takes each axis to a different axis,
+/// possibly flipped, without mirroring.
+pub fn rotations() -> Vec<Rotation> {
+    let permutations = [[0, 1, 2], [0, 2, 1], [1, 0, 2], [1, 2, 0], [2, 0, 1], [2, 1, 0]];
+    let mut rotations = vec![];
+    for permutation in permutations.iter() {
+        for signs in 0..8 {
+            let mut matrix = [[0; 3]; 3];
+            for (row, column) in permutation.iter().enumerate() {
+                matrix[row][*column] = if (signs >> row) & 1 == 1 { -1 } else { 1 };
+            }
+            let rotation = Rotation(matrix);
+            if rotation.determinant() == 1 {
+                rotations.push(rotation);
+            }
+        }
+    }
+    rotations
+}
+
+/// Parses each scanner's report, a `--- scanner N ---` header followed by the positions of the
+/// beacons it can see, with blank lines between the reports.
+pub fn parse_reports(input: &PuzzleInput) -> anyhow::Result<Vec<Vec<Point>>> {
+    let reports = input.sections().iter().map(parse_report).collect::<anyhow::Result<Vec<Vec<Point>>>>()?;
+    if reports.is_empty() {
+        return Err(anyhow::anyhow!("The input is empty"));
+    }
+    Ok(reports)
+}
+
+fn parse_report(section: &Section) -> anyhow::Result<Vec<Point>> {
+    let header = section.lines[0].trim();
+    if !header.starts_with("--- scanner ") || !header.ends_with(" ---") {
+        return Err(error_at(section.first_line, column_of(&section.lines[0], header), format!("expected a header such as '--- scanner 0 ---', found '{}'", header)));
+    }
+    (section.first_line + 1..).zip(&section.lines[1..]).map(|(i, line)| {
+        let trimmed = line.trim();
+        let coords = trimmed.split(',').map(|coord| parse_at::<i64>(i, line, coord.trim())).collect::<anyhow::Result<Vec<i64>>>()?;
+        match coords.as_slice() {
+            [x, y, z] => Ok(Point::new(*x, *y, *z)),
+            _ => Err(error_at(i, column_of(line, trimmed), format!("expected a beacon as three numbers separated by commas, found '{}'", trimmed))),
+        }
+    }).collect()
+}
+
+/// For each beacon, the squared distances to every other beacon seen by the same scanner. These
+/// don't change when the scanner is turned or moved, so a beacon seen by two scanners has many
+/// of the same distances in both.
+pub fn fingerprints(beacons: &[Point]) -> Vec<HashSet<i64>> {
+    beacons.iter()
+        .map(|beacon| beacons.iter().filter(|other| *other != beacon).map(|other| beacon.squared_distance(other)).collect())
+        .collect()
+}
+
+/// Finds how `scanner` is turned and where it is, given the beacons of a scanner that has already
+/// been placed. Two scanners overlap if they see at least 12 of the same beacons, so a beacon they
+/// both see shares at least 11 distances between the two. Trying each rotation of a matching pair
+/// of beacons gives the scanner's position, which is right if 12 beacons then line up.
+pub fn align(known: &[Point], known_fingerprints: &[HashSet<i64>], scanner: &[Point], scanner_fingerprints: &[HashSet<i64>]) -> Option<(Rotation, Point)> {
+    let known_set = known.iter().copied().collect::<HashSet<Point>>();
+    let rotations = rotations();
+    for (i, known_fingerprint) in known_fingerprints.iter().enumerate() {
+        for (j, scanner_fingerprint) in scanner_fingerprints.iter().enumerate() {
+            if known_fingerprint.intersection(scanner_fingerprint).count() < 11 {
+                continue;
+            }
+            for rotation in rotations.iter() {
+                let position = known[i] - rotation.apply(scanner[j]);
+                let shared = scanner.iter().filter(|beacon| known_set.contains(&(rotation.apply(**beacon) + position))).count();
+                if shared >= 12 {
+                    return Some((*rotation, position));
+                }
+            }
+        }
+    }
+    None
+}
+
+/// Every beacon and scanner, relative to the first scanner.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Map {
+    pub scanners: Vec<Point>,
+    pub beacons: HashSet<Point>,
+}
+
+impl Map {
+    pub fn largest_scanner_distance(&self) -> i64 {
+        self.scanners.iter()
+            .flat_map(|a| self.scanners.iter().map(move |b| a.manhattan_distance(b)))
+            .max()
+            .unwrap_or(0)
+    }
+}
+
+/// Places every scanner by aligning it with one that has already been placed, starting from the
+/// first scanner.
+pub fn assemble_map(reports: &[Vec<Point>]) -> anyhow::Result<Map> {
+    let first = reports.first().ok_or_else(|| anyhow::anyhow!("There are no scanner reports"))?;
+    let prints = reports.iter().map(|report| fingerprints(report)).collect::<Vec<Vec<HashSet<i64>>>>();
+    let mut placed: Vec<Option<(Point, Vec<Point>)>> = vec![None; reports.len()];
+    placed[0] = Some((Point::default(), first.clone()));
+    let mut to_visit = vec![0];
+
+    while let Some(i) = to_visit.pop() {
+        let known = match &placed[i] {
+            Some((_, beacons)) => beacons.clone(),
+            None => continue,
+        };
+        for j in 0..reports.len() {
+            if placed[j].is_some() {
+                continue;
+            }
+            if let Some((rotation, position)) = align(&known, &prints[i], &reports[j], &prints[j]) {
+                let beacons = reports[j].iter().map(|beacon| rotation.apply(*beacon) + position).collect();
+                placed[j] = Some((position, beacons));
+                to_visit.push(j);
+            }
+        }
+    }
+
+    let mut map = Map { scanners: vec![], beacons: HashSet::new() };
+    for (i, scanner) in placed.into_iter().enumerate() {
+        let (position, beacons) = scanner.ok_or_else(|| anyhow::anyhow!("Scanner {} does not overlap with enough of the others to be placed", i))?;
+        map.scanners.push(position);
+        map.beacons.extend(beacons);
+    }
+    Ok(map)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use crate::day_19::*;
+
+    /// Makes up beacons seen by several scanners, each turned a different way, and writes out what
+    /// each scanner reports. Returns the input along with every beacon that some scanner can see.
+    fn get_test_input(scanners: &[(Point, usize)]) -> (String, HashSet<Point>) {
+        let mut seed: i64 = 19;
+        let mut random = move || {
+            seed = (seed * 1103515245 + 12345) % 2147483648;
+            seed % 2001 - 1000
+        };
+        // Plenty of beacons around each scanner, so that neighbouring scanners share some.
+        let beacons = scanners.iter()
+            .flat_map(|(scanner, _)| (0..40).map(|_| *scanner + Point::new(random(), random(), random())).collect::<Vec<Point>>())
+            .collect::<HashSet<Point>>();
+
+        let rotations = rotations();
+        let mut input = String::new();
+        let mut seen = HashSet::new();
+        for (i, (scanner, rotation)) in scanners.iter().enumerate() {
+            input += &format!("--- scanner {} ---\n", i);
+            for beacon in beacons.iter() {
+                let relative = *beacon - *scanner;
+                if relative.x.abs() <= 1000 && relative.y.abs() <= 1000 && relative.z.abs() <= 1000 {
+                    let reading = rotations[*rotation].apply(relative);
+                    input += &format!("{},{},{}\n", reading.x, reading.y, reading.z);
+                    seen.insert(*beacon);
+                }
+            }
+            input += "\n";
+        }
+        (input, seen)
+    }
+
+    #[test]
+    fn test_rotations() {
+        let rotations = rotations();
+        assert_eq!(rotations.len(), 24);
+        assert_eq!(rotations.iter().collect::<HashSet<&Rotation>>().len(), 24);
+        assert!(rotations.contains(&Rotation::IDENTITY));
+        let point = Point::new(1, 2, 3);
+        let turned = rotations.iter().map(|rotation| rotation.apply(point)).collect::<HashSet<Point>>();
+        assert_eq!(turned.len(), 24);
+        assert!(turned.contains(&Point::new(-2, 1, 3)));
+        assert!(!turned.contains(&Point::new(-1, 2, 3)));
+    }
+
+    #[test]
+    fn test_example() {
+        let reports = parse_reports(&PuzzleInput::from(DAY.examples[0].input)).unwrap();
+        assert_eq!(reports.len(), 5);
+        let map = assemble_map(&reports).unwrap();
+        assert_eq!(map.scanners, [
+            Point::new(0, 0, 0), Point::new(68, -1246, -43), Point::new(1105, -1205, 1229),
+            Point::new(-92, -2380, -20), Point::new(-20, -1133, 1061),
+        ]);
+        assert_eq!(map.beacons.len(), 79);
+        assert!(map.beacons.contains(&Point::new(-618, -824, -621)));
+        assert!(map.beacons.contains(&Point::new(1889, -1729, 1762)));
+        assert_eq!(map.largest_scanner_distance(), 3621);
+        assert_eq!(assemble_map(&[]).unwrap_err().to_string(), "There are no scanner reports");
+    }
+
+    #[test]
+    fn test_assemble_map() {
+        let scanners = [
+            (Point::new(0, 0, 0), 0),
+            (Point::new(68, -1246, -43), 7),
+            (Point::new(1105, -1205, 1229), 13),
+            (Point::new(-92, -2380, -20), 22),
+            (Point::new(-20, -1133, 1061), 5),
+        ];
+        let (input, seen) = get_test_input(&scanners);
+        let reports = parse_reports(&PuzzleInput::from(input.as_str())).unwrap();
+        let map = assemble_map(&reports).unwrap();
+        assert_eq!(map.scanners, scanners.iter().map(|(scanner, _)| *scanner).collect::<Vec<Point>>());
+        assert_eq!(map.beacons, seen);
+        assert_eq!(map.largest_scanner_distance(), 3621);
+    }
+
+    #[test]
+    fn test_unplaceable_scanner() {
+        let (input, _) = get_test_input(&[(Point::new(0, 0, 0), 0), (Point::new(5000, 0, 0), 3)]);
+        let reports = parse_reports(&PuzzleInput::from(input.as_str())).unwrap();
+        assert_eq!(assemble_map(&reports).unwrap_err().to_string(), "Scanner 1 does not overlap with enough of the others to be placed");
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let parse = |s: &str| parse_reports(&PuzzleInput::from(s)).unwrap_err().to_string();
+        assert_eq!(parse("--- scanner 0\n1,2,3"), "line 1, column 1: expected a header such as '--- scanner 0 ---', found '--- scanner 0'");
+        assert_eq!(parse("--- scanner 0 ---\n1,2,3\n\n--- scanner 1 ---\n1,2"), "line 5, column 1: expected a beacon as three numbers separated by commas, found '1,2'");
+        assert_eq!(parse("--- scanner 0 ---\n1,x,3"), "line 2, column 3: could not parse 'x': invalid digit found in string");
+        assert_eq!(parse(""), "The input is empty");
+    }
+}
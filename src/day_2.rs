@@ -1,3 +1,9 @@
+use crate::input::PuzzleInput;
+use crate::registry::Example;
+use crate::solution::{Answer, Solution};
+use crate::utils::{column_of, error_at, parse_at};
+
+
 pub struct Position {
     pub horizontal: i64,
     pub depth: i64,
@@ -10,13 +16,14 @@ pub enum Direction {
     Up,
 }
 
+#[derive(Debug)]
 pub struct Instruction {
     direction: Direction,
     distance: i64,
 }
 
 fn parse_direction(dir_str: &str) -> Option<Direction> {
-    return match dir_str {
+    match dir_str {
         "forward" => Some(Direction::Forward),
         "down" => Some(Direction::Down),
         "up" => Some(Direction::Up),
@@ -24,26 +31,30 @@ fn parse_direction(dir_str: &str) -> Option<Direction> {
     }
 }
 
-fn parse_instruction(instruction_str: &String) -> Option<Instruction> {
-    let parts = instruction_str.trim().split_whitespace().collect::<Vec<&str>>();
+fn parse_instruction(line_index: usize, instruction_str: &str) -> anyhow::Result<Instruction> {
+    let parts = instruction_str.split_whitespace().collect::<Vec<&str>>();
     if parts.len() != 2 {
-        return None;
+        return Err(error_at(line_index, 0, format!("expected a direction and a distance, found '{}'", instruction_str.trim())));
     }
-    let direction = parse_direction(parts[0])?;
-    let distance = parts[1].parse::<i64>().ok()?;
-    return Some(Instruction {
-        direction: direction,
-        distance: distance,
-    });
+    let direction = parse_direction(parts[0])
+        .ok_or_else(|| error_at(line_index, column_of(instruction_str, parts[0]), format!("unknown direction '{}'", parts[0])))?;
+    let distance = parse_at(line_index, instruction_str, parts[1])?;
+    Ok(Instruction {
+        direction,
+        distance,
+    })
 }
 
-fn parse_instructions(instructions_str: &Vec<String>) -> Vec<Instruction> {
-    return instructions_str.iter()
-        .filter_map(|instr| parse_instruction(instr))
-        .collect::<Vec<Instruction>>();
+/// Parses the course, one instruction per non-empty line.
+pub fn parse_instructions(instructions_str: &[String]) -> anyhow::Result<Vec<Instruction>> {
+    instructions_str.iter().enumerate()
+        .filter(|(_, instr)| !instr.trim().is_empty())
+        .map(|(i, instr)| parse_instruction(i, instr))
+        .collect()
 }
 
-fn calculate_position_part1(course: &Vec<Instruction>) -> Position {
+/// Follows the course, with `down` and `up` changing the depth directly.
+pub fn calculate_position_part1(course: &[Instruction]) -> Position {
     let mut horizontal: i64 = 0;
     let mut depth: i64 = 0;
 
@@ -61,13 +72,14 @@ fn calculate_position_part1(course: &Vec<Instruction>) -> Position {
         }
     }
 
-    return Position {
-        horizontal: horizontal,
-        depth: depth,
+    Position {
+        horizontal,
+        depth,
     }
 }
 
-fn calculate_position_part2(course: &Vec<Instruction>) -> Position {
+/// Follows the course, with `down` and `up` changing the aim, which `forward` then uses to change the depth.
+pub fn calculate_position_part2(course: &[Instruction]) -> Position {
     let mut horizontal: i64 = 0;
     let mut depth: i64 = 0;
     let mut aim: i64 = 0;
@@ -87,20 +99,58 @@ fn calculate_position_part2(course: &Vec<Instruction>) -> Position {
         }
     }
 
-    return Position {
-        horizontal: horizontal,
-        depth: depth,
+    Position {
+        horizontal,
+        depth,
     }
 }
 
-pub fn part_1(instructions: &Vec<String>) {
-    let position = calculate_position_part1(&parse_instructions(instructions));
-    println!("Position: h {} d {}, Multiplied: {}", position.horizontal, position.depth, position.horizontal * position.depth);
+/// Day 2: Dive!
+/// https://adventofcode.com/2021/day/2
+pub struct Day2;
+
+impl Solution for Day2 {
+    type Input = Vec<Instruction>;
+
+    fn parse(input: &PuzzleInput) -> anyhow::Result<Self::Input> {
+        parse_instructions(input.lines())
+    }
+
+    fn part_1(course: &Self::Input) -> anyhow::Result<Answer> {
+        let position = calculate_position_part1(course);
+        Ok(Answer::from(position.horizontal * position.depth).with_details(vec![
+            ("horizontal", position.horizontal.into()),
+            ("depth", position.depth.into()),
+        ]))
+    }
+
+    fn part_2(course: &Self::Input) -> anyhow::Result<Answer> {
+        let position = calculate_position_part2(course);
+        Ok(Answer::from(position.horizontal * position.depth).with_details(vec![
+            ("horizontal", position.horizontal.into()),
+            ("depth", position.depth.into()),
+        ]))
+    }
 }
 
-pub fn part_2(instructions: &Vec<String>) {
-    let position = calculate_position_part2(&parse_instructions(instructions));
-    println!("Position: h {} d {}, Multiplied: {}", position.horizontal, position.depth, position.horizontal * position.depth);
+register_day! {
+    day: 2,
+    title: "Dive!",
+    solution: Day2,
+    parts: [1, 2],
+    examples: [
+        Example {
+            input: r#"forward 5
+down 5
+forward 8
+up 3
+down 8
+forward 2
+"#,
+            part_1: Some("150"),
+            part_2: Some("900"),
+        },
+    ],
 }
 
 
@@ -112,7 +162,7 @@ mod tests {
         vec!["".to_string()]
     }
     fn get_input() -> Vec<String> {
-        let input = vec![
+        let input = [
             "forward 5",
             "down 5",
             "forward 8",
@@ -130,13 +180,21 @@ mod tests {
 
     #[test]
     fn test_calculate_position_part1() {
-        assert_position(&calculate_position_part1(&parse_instructions(&get_empty_input())), 0, 0);
-        assert_position(&calculate_position_part1(&parse_instructions(&get_input())), 15, 10);
+        assert_position(&calculate_position_part1(&parse_instructions(&get_empty_input()).unwrap()), 0, 0);
+        assert_position(&calculate_position_part1(&parse_instructions(&get_input()).unwrap()), 15, 10);
     }
 
     #[test]
     fn test_calculate_position_part2() {
-        assert_position(&calculate_position_part2(&parse_instructions(&get_empty_input())), 0, 0);
-        assert_position(&calculate_position_part2(&parse_instructions(&get_input())), 15, 60);
+        assert_position(&calculate_position_part2(&parse_instructions(&get_empty_input()).unwrap()), 0, 0);
+        assert_position(&calculate_position_part2(&parse_instructions(&get_input()).unwrap()), 15, 60);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let parse = |s: &str| parse_instructions(&[s.to_string()]).unwrap_err().to_string();
+        assert_eq!(parse("forward"), "line 1, column 1: expected a direction and a distance, found 'forward'");
+        assert_eq!(parse("backward 5"), "line 1, column 1: unknown direction 'backward'");
+        assert_eq!(parse("down x"), "line 1, column 6: could not parse 'x': invalid digit found in string");
     }
 }
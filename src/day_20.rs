@@ -0,0 +1,211 @@
+use std::fmt;
+use crate::input::PuzzleInput;
+use crate::registry::Example;
+use crate::solution::{Answer, Solution};
+use crate::utils::{error_at, Grid};
+
+
+/// Day 20: Trench Map
+/// https://adventofcode.com/2021/day/20
+pub struct Day20;
+
+impl Solution for Day20 {
+    type Input = TrenchMap;
+
+    fn parse(input: &PuzzleInput) -> anyhow::Result<Self::Input> {
+        parse_input(input)
+    }
+
+    fn part_1(trench_map: &Self::Input) -> anyhow::Result<Answer> {
+        Ok(trench_map.enhance_n(2).lit_count()?.into())
+    }
+
+    fn part_2(trench_map: &Self::Input) -> anyhow::Result<Answer> {
+        Ok(trench_map.enhance_n(50).lit_count()?.into())
+    }
+}
+
+register_day! {
+    day: 20,
+    title: "Trench Map",
+    solution: Day20,
+    parts: [1, 2],
+    examples: [
+        Example {
+            input: r#"..#.#..#####.#.#.#.###.##.....###.##.#..###.####..#####..#....#..#..##..###..######.###...####..#..#####..##..#.#####...##.#.#..#.##..#.#......#.###.######.###.####...#.##.##..#..#..#####.....#.#....###..#.##......#.....#..#..#..##..#...##.######.####.####.#.#...#.......#..#.#.#...####.##.#......#..#...##.#.##..#...##.#.##..###.#......#.#.......#.#.#.####.###.##...#.....####.#..#..#.##.#....##..#.####....##...##..#...#......#.#.......#.......##..####..#...#.#.#...##..#.#..###..#####........#..####......#..#
+
+#..#.
+#....
+##..#
+..#..
+..###
+"#,
+            part_1: Some("35"),
+            part_2: Some("3351"),
+        },
+    ],
+}
+
+
+/// The enhancement algorithm, and the image to enhance.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TrenchMap {
+    pub algorithm: Vec<bool>,
+    pub image: Image,
+}
+
+impl TrenchMap {
+    /// Enhances the image `n` times.
+    pub fn enhance_n(&self, n: usize) -> Image {
+        (0..n).fold(self.image.clone(), |image, _| image.enhance(&self.algorithm))
+    }
+}
+
+/// An infinite image, stored as the rectangle that can differ from the rest, and whether the
+/// pixels outside of it are lit.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Image {
+    pub pixels: Grid<bool>,
+    pub background: bool,
+}
+
+impl Image {
+    /// Whether the pixel at `(i, j)` is lit, where the rectangle's top left is `(0, 0)` and
+    /// anything outside of it is the background.
+    pub fn get(&self, i: i64, j: i64) -> bool {
+        if i < 0 || j < 0 {
+            return self.background;
+        }
+        *self.pixels.get((i as usize, j as usize)).unwrap_or(&self.background)
+    }
+
+    /// Replaces each pixel with the algorithm's entry for the 3x3 square around it, read as a
+    /// binary number from the top left. The rectangle grows by one pixel on each side, as the
+    /// squares around those pixels overlap it. Every pixel of the background sees a square of
+    /// background, so it all becomes the first entry of the algorithm if it was dark, or the last
+    /// if it was lit, which flips the infinite background when the first entry is `#`.
+    pub fn enhance(&self, algorithm: &[bool]) -> Image {
+        let pixels = Grid::from_fn(self.pixels.height() + 2, self.pixels.width() + 2, |(i, j)| {
+            // (i, j) in the new rectangle is (i - 1, j - 1) in the old one.
+            let (i, j) = (i as i64 - 1, j as i64 - 1);
+            let index = (-1..=1)
+                .flat_map(|di| (-1..=1).map(move |dj| (di, dj)))
+                .fold(0, |index, (di, dj)| index * 2 + self.get(i + di, j + dj) as usize);
+            algorithm[index]
+        });
+        let background = algorithm[if self.background { 511 } else { 0 }];
+        Image { pixels, background }
+    }
+
+    /// How many pixels are lit, which fails if the background is lit as there are infinitely many.
+    pub fn lit_count(&self) -> anyhow::Result<usize> {
+        if self.background {
+            return Err(anyhow::anyhow!("Infinitely many pixels are lit, as the background is lit"));
+        }
+        Ok(self.pixels.iter().filter(|pixel| **pixel).count())
+    }
+
+    /// Draws the rectangle as in the puzzle description, with `#` for lit pixels and `.` for dark.
+    pub fn render(&self) -> Grid<char> {
+        self.pixels.map(|pixel| if *pixel { '#' } else { '.' })
+    }
+}
+
+impl fmt::Display for Image {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.render())
+    }
+}
+
+/// Parses the algorithm, which may be wrapped over several lines, then a blank line and the image.
+pub fn parse_input(input: &PuzzleInput) -> anyhow::Result<TrenchMap> {
+    let sections = input.sections();
+    let (algorithm_section, image_section) = match sections.as_slice() {
+        [algorithm, image] => (algorithm, image),
+        _ => return Err(anyhow::anyhow!("Expected the algorithm and the image separated by a blank line, found {} sections", sections.len())),
+    };
+
+    let mut algorithm = vec![];
+    for (i, line) in (algorithm_section.first_line..).zip(algorithm_section.lines) {
+        algorithm.extend(parse_pixels(i, line)?);
+    }
+    if algorithm.len() != 512 {
+        return Err(error_at(algorithm_section.first_line, 0, format!("expected the algorithm to be 512 pixels long, found {}", algorithm.len())));
+    }
+
+    let rows = (image_section.first_line..).zip(image_section.lines)
+        .map(|(i, line)| parse_pixels(i, line))
+        .collect::<anyhow::Result<Vec<Vec<bool>>>>()?;
+    let width = rows[0].len();
+    if let Some(i) = rows.iter().position(|row| row.len() != width) {
+        return Err(error_at(image_section.first_line + i, 0, format!("expected {} pixels like the first row, found {}", width, rows[i].len())));
+    }
+    Ok(TrenchMap { algorithm, image: Image { pixels: Grid::from_rows(rows)?, background: false } })
+}
+
+/// Parses a row of `#` for lit pixels and `.` for dark ones.
+fn parse_pixels(line_index: usize, line: &str) -> anyhow::Result<Vec<bool>> {
+    line.char_indices().map(|(j, c)| match c {
+        '#' => Ok(true),
+        '.' => Ok(false),
+        _ => Err(error_at(line_index, j, format!("expected '#' or '.', found '{}'", c))),
+    }).collect()
+}
+
+
+#[cfg(test)]
+mod tests {
+    use crate::day_20::*;
+
+    const TEST_IMAGE: &str = r#"#..#.
+#....
+##..#
+..#..
+..###"#;
+
+    fn get_test_input(algorithm: &str) -> TrenchMap {
+        parse_input(&PuzzleInput::from(format!("{}\n\n{}", algorithm, TEST_IMAGE).as_str())).unwrap()
+    }
+
+    #[test]
+    fn test_enhance() {
+        let trench_map = get_test_input(DAY.examples[0].input.lines().next().unwrap());
+        assert_eq!(trench_map.image.to_string(), TEST_IMAGE);
+        let once = trench_map.enhance_n(1);
+        assert_eq!(once.to_string(), r#".##.##.
+#..#.#.
+##.#..#
+####..#
+.#..##.
+..##..#
+...#.#."#);
+        assert_eq!(once.lit_count().unwrap(), 24);
+        assert_eq!(trench_map.enhance_n(2).lit_count().unwrap(), 35);
+        assert_eq!(trench_map.enhance_n(50).lit_count().unwrap(), 3351);
+    }
+
+    #[test]
+    fn test_flipping_background() {
+        // Dark squares light up, and lit squares go dark, so the background flips every step.
+        let algorithm = (0..512).map(|i| if i == 0 || (i % 3 == 1 && i != 511) { '#' } else { '.' }).collect::<String>();
+        let trench_map = get_test_input(&algorithm);
+        let once = trench_map.enhance_n(1);
+        assert!(once.background);
+        assert!(once.lit_count().is_err());
+        assert!(once.get(-5, 100));
+        let twice = trench_map.enhance_n(2);
+        assert!(!twice.background);
+        assert_eq!((twice.pixels.height(), twice.pixels.width()), (9, 9));
+        assert!(twice.lit_count().is_ok());
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let parse = |s: &str| parse_input(&PuzzleInput::from(s)).unwrap_err().to_string();
+        assert_eq!(parse("#.#\n\n#.\n.#"), "line 1, column 1: expected the algorithm to be 512 pixels long, found 3");
+        let algorithm = ".".repeat(512);
+        assert_eq!(parse(&format!("{}\n\n#.\n.x", algorithm)), "line 4, column 2: expected '#' or '.', found 'x'");
+        assert_eq!(parse(&format!("{}\n\n#.\n.", algorithm)), "line 4, column 1: expected 2 pixels like the first row, found 1");
+        assert_eq!(parse(&algorithm), "Expected the algorithm and the image separated by a blank line, found 1 sections");
+    }
+}
@@ -0,0 +1,180 @@
+use std::collections::HashMap;
+use crate::input::PuzzleInput;
+use crate::registry::Example;
+use crate::solution::{Answer, Solution};
+use crate::utils::{column_of, error_at, parse_at};
+
+
+/// Day 21: Dirac Dice
+/// https://adventofcode.com/2021/day/21
+pub struct Day21;
+
+impl Solution for Day21 {
+    type Input = [u64; 2];
+
+    fn parse(input: &PuzzleInput) -> anyhow::Result<Self::Input> {
+        parse_starting_positions(input.lines())
+    }
+
+    fn part_1(starts: &Self::Input) -> anyhow::Result<Answer> {
+        let game = play_deterministic(*starts, 1000);
+        Ok(Answer::from(game.losing_score * game.rolls).with_details(vec![
+            ("losing_score", game.losing_score.into()),
+            ("rolls", game.rolls.into()),
+        ]))
+    }
+
+    fn part_2(starts: &Self::Input) -> anyhow::Result<Answer> {
+        let [player_1, player_2] = count_wins(*starts, 21)?;
+        Ok(Answer::from(player_1.max(player_2)).with_details(vec![
+            ("player_1_wins", player_1.into()),
+            ("player_2_wins", player_2.into()),
+        ]))
+    }
+}
+
+register_day! {
+    day: 21,
+    title: "Dirac Dice",
+    solution: Day21,
+    parts: [1, 2],
+    examples: [
+        Example {
+            input: r#"Player 1 starting position: 4
+Player 2 starting position: 8
+"#,
+            part_1: Some("739785"),
+            part_2: Some("444356092776315"),
+        },
+    ],
+}
+
+
+/// Parses each player's starting space, from lines such as `Player 1 starting position: 4`.
+pub fn parse_starting_positions(lines: &[String]) -> anyhow::Result<[u64; 2]> {
+    let lines = lines.iter().enumerate().filter(|(_, line)| !line.trim().is_empty()).collect::<Vec<(usize, &String)>>();
+    if lines.len() != 2 {
+        return Err(anyhow::anyhow!("Expected a starting position for each of the 2 players, found {} lines", lines.len()));
+    }
+    let mut starts = [0; 2];
+    for (player, (i, line)) in lines.into_iter().enumerate() {
+        let trimmed = line.trim();
+        let prefix = format!("Player {} starting position: ", player + 1);
+        let position = trimmed.strip_prefix(prefix.as_str())
+            .ok_or_else(|| error_at(i, column_of(line, trimmed), format!("expected the line to start with '{}'", prefix)))?;
+        starts[player] = parse_at(i, line, position)?;
+        if !(1..=10).contains(&starts[player]) {
+            return Err(error_at(i, column_of(line, position), format!("expected a space from 1 to 10, found {}", starts[player])));
+        }
+    }
+    Ok(starts)
+}
+
+/// Moves `roll` spaces forwards around the track of spaces 1 to 10.
+fn advance(position: u64, roll: u64) -> u64 {
+    (position - 1 + roll) % 10 + 1
+}
+
+/// How a game with the deterministic die ended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DeterministicGame {
+    pub losing_score: u64,
+    pub rolls: u64,
+}
+
+/// Plays with the die that rolls 1, 2, 3, ... 100 and then starts again from 1, with each player
+/// rolling three times a turn, until someone reaches `winning_score`.
+pub fn play_deterministic(starts: [u64; 2], winning_score: u64) -> DeterministicGame {
+    let mut positions = starts;
+    let mut scores = [0; 2];
+    let mut rolls = 0;
+    for player in (0..2).cycle() {
+        let roll = (0..3).map(|_| {
+            rolls += 1;
+            (rolls - 1) % 100 + 1
+        }).sum::<u64>();
+        positions[player] = advance(positions[player], roll);
+        scores[player] += positions[player];
+        if scores[player] >= winning_score {
+            return DeterministicGame { losing_score: scores[1 - player], rolls };
+        }
+    }
+    unreachable!("the players take turns forever until one wins")
+}
+
+/// How many of the 27 ways of rolling the Dirac die three times give each total from 3 to 9.
+const ROLL_FREQUENCIES: [(u64, u128); 7] = [(3, 1), (4, 3), (5, 6), (6, 7), (7, 6), (8, 3), (9, 1)];
+
+/// A player's position and score.
+type PlayerState = (u64, u64);
+
+/// The wins for each player from a state, keyed by the player whose turn it is and then the other.
+type WinsMemo = HashMap<(PlayerState, PlayerState), (u128, u128)>;
+
+/// Counts the universes each player wins in when every roll of the Dirac die splits the universe
+/// into one for each face, and the first to `winning_score` wins. The counts grow quickly with the
+/// winning score, overflowing a `u64` not far past 21, so this fails if they don't fit in a `u128`.
+pub fn count_wins(starts: [u64; 2], winning_score: u64) -> anyhow::Result<[u128; 2]> {
+    let mut memo = HashMap::new();
+    let (player_1, player_2) = count_wins_from((starts[0], 0), (starts[1], 0), winning_score, &mut memo)
+        .ok_or_else(|| anyhow::anyhow!("The number of universes for a winning score of {} does not fit in 128 bits", winning_score))?;
+    Ok([player_1, player_2])
+}
+
+/// Counts the wins for the player whose turn it is and the other player, from where they are now.
+/// The game only depends on the positions and scores, with whose turn it is given by the order of
+/// the players, so swapping them each turn lets every state be remembered from either side.
+/// Returns `None` if the counts overflow.
+fn count_wins_from(current: PlayerState, other: PlayerState, winning_score: u64, memo: &mut WinsMemo) -> Option<(u128, u128)> {
+    if let Some(wins) = memo.get(&(current, other)) {
+        return Some(*wins);
+    }
+
+    let mut wins: (u128, u128) = (0, 0);
+    for (roll, frequency) in ROLL_FREQUENCIES.iter() {
+        let position = advance(current.0, *roll);
+        let score = current.1 + position;
+        if score >= winning_score {
+            wins.0 = wins.0.checked_add(*frequency)?;
+        } else {
+            let (other_wins, current_wins) = count_wins_from(other, (position, score), winning_score, memo)?;
+            wins.0 = wins.0.checked_add(frequency.checked_mul(current_wins)?)?;
+            wins.1 = wins.1.checked_add(frequency.checked_mul(other_wins)?)?;
+        }
+    }
+
+    memo.insert((current, other), wins);
+    Some(wins)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use crate::day_21::*;
+
+    #[test]
+    fn test_deterministic() {
+        assert_eq!(play_deterministic([4, 8], 1000), DeterministicGame { losing_score: 745, rolls: 993 });
+        // Player 1 rolls 1+2+3 and moves from 4 to 10, winning straight away.
+        assert_eq!(play_deterministic([4, 8], 10), DeterministicGame { losing_score: 0, rolls: 3 });
+    }
+
+    #[test]
+    fn test_count_wins() {
+        assert_eq!(count_wins([4, 8], 21).unwrap(), [444356092776315, 341960390180808]);
+        // Every roll of player 1's first turn reaches a score of at least 1, before player 2 rolls.
+        assert_eq!(count_wins([4, 8], 1).unwrap(), [27, 0]);
+        let [player_1, player_2] = count_wins([4, 8], 30).unwrap();
+        assert!(player_1 > u64::MAX as u128 && player_2 > 341960390180808);
+        assert_eq!(count_wins([4, 8], 200).unwrap_err().to_string(), "The number of universes for a winning score of 200 does not fit in 128 bits");
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let parse = |s: &str| parse_starting_positions(&s.split('\n').map(str::to_string).collect::<Vec<String>>()).unwrap_err().to_string();
+        assert_eq!(parse("Player 1 starting position: 4"), "Expected a starting position for each of the 2 players, found 1 lines");
+        assert_eq!(parse("Player 1 starting position: 4\nPlayer 1 starting position: 8"), "line 2, column 1: expected the line to start with 'Player 2 starting position: '");
+        assert_eq!(parse("Player 1 starting position: 4\nPlayer 2 starting position: 11"), "line 2, column 29: expected a space from 1 to 10, found 11");
+        assert_eq!(parse("Player 1 starting position: four\nPlayer 2 starting position: 8"), "line 1, column 29: could not parse 'four': invalid digit found in string");
+    }
+}
@@ -0,0 +1,335 @@
+use std::collections::HashMap;
+use crate::input::PuzzleInput;
+use crate::registry::Example;
+use crate::solution::{Answer, Solution};
+use crate::utils::{column_of, error_at, parse_range};
+
+
+/// Day 22: Reactor Reboot
+/// https://adventofcode.com/2021/day/22
+pub struct Day22;
+
+impl Solution for Day22 {
+    type Input = Vec<Step>;
+
+    fn parse(input: &PuzzleInput) -> anyhow::Result<Self::Input> {
+        parse_steps(input.lines())
+    }
+
+    fn part_1(steps: &Self::Input) -> anyhow::Result<Answer> {
+        Ok(reboot(steps, Some(&INITIALIZATION_REGION)).into())
+    }
+
+    fn part_2(steps: &Self::Input) -> anyhow::Result<Answer> {
+        Ok(reboot(steps, None).into())
+    }
+}
+
+register_day! {
+    day: 22,
+    title: "Reactor Reboot",
+    solution: Day22,
+    parts: [1, 2],
+    examples: [
+        Example {
+            input: r#"on x=10..12,y=10..12,z=10..12
+on x=11..13,y=11..13,z=11..13
+off x=9..11,y=9..11,z=9..11
+on x=10..10,y=10..10,z=10..10
+"#,
+            part_1: Some("39"),
+            part_2: None,
+        },
+        Example {
+            input: r#"on x=-20..26,y=-36..17,z=-47..7
+on x=-20..33,y=-21..23,z=-26..28
+on x=-22..28,y=-29..23,z=-38..16
+on x=-46..7,y=-6..46,z=-50..-1
+on x=-49..1,y=-3..46,z=-24..28
+on x=2..47,y=-22..22,z=-23..27
+on x=-27..23,y=-28..26,z=-21..29
+on x=-39..5,y=-6..47,z=-3..44
+on x=-30..21,y=-8..43,z=-13..34
+on x=-22..26,y=-27..20,z=-29..19
+off x=-48..-32,y=26..41,z=-47..-37
+on x=-12..35,y=6..50,z=-50..-2
+off x=-48..-32,y=-32..-16,z=-15..-5
+on x=-18..26,y=-33..15,z=-7..46
+off x=-40..-22,y=-38..-28,z=23..41
+on x=-16..35,y=-41..10,z=-47..6
+off x=-32..-23,y=11..30,z=-14..3
+on x=-49..-5,y=-3..45,z=-29..18
+off x=18..30,y=-20..-8,z=-3..13
+on x=-41..9,y=-7..43,z=-33..15
+on x=-54112..-39298,y=-85059..-49293,z=-27449..7877
+on x=967..23432,y=45373..81175,z=27513..53682
+"#,
+            part_1: Some("590784"),
+            part_2: None,
+        },
+        Example {
+            input: r#"on x=-5..47,y=-31..22,z=-19..33
+on x=-44..5,y=-27..21,z=-14..35
+on x=-49..-1,y=-11..42,z=-10..38
+on x=-20..34,y=-40..6,z=-44..1
+off x=26..39,y=40..50,z=-2..11
+on x=-41..5,y=-41..6,z=-36..8
+off x=-43..-33,y=-45..-28,z=7..25
+on x=-33..15,y=-32..19,z=-34..11
+off x=35..47,y=-46..-34,z=-11..5
+on x=-14..36,y=-6..44,z=-16..29
+on x=-57795..-6158,y=29564..72030,z=20435..90618
+on x=36731..105352,y=-21140..28532,z=16094..90401
+on x=30999..107136,y=-53464..15513,z=8553..71215
+on x=13528..83982,y=-99403..-27377,z=-24141..23996
+on x=-72682..-12347,y=18159..111354,z=7391..80950
+on x=-1060..80757,y=-65301..-20884,z=-103788..-16709
+on x=-83015..-9461,y=-72160..-8347,z=-81239..-26856
+on x=-52752..22273,y=-49450..9096,z=54442..119054
+on x=-29982..40483,y=-108474..-28371,z=-24328..38471
+on x=-4958..62750,y=40422..118853,z=-7672..65583
+on x=55694..108686,y=-43367..46958,z=-26781..48729
+on x=-98497..-18186,y=-63569..3412,z=1232..88485
+on x=-726..56291,y=-62629..13224,z=18033..85226
+on x=-110886..-34664,y=-81338..-8658,z=8914..63723
+on x=-55829..24974,y=-16897..54165,z=-121762..-28058
+on x=-65152..-11147,y=22489..91432,z=-58782..1780
+on x=-120100..-32970,y=-46592..27473,z=-11695..61039
+on x=-18631..37533,y=-124565..-50804,z=-35667..28308
+on x=-57817..18248,y=49321..117703,z=5745..55881
+on x=14781..98692,y=-1341..70827,z=15753..70151
+on x=-34419..55919,y=-19626..40991,z=39015..114138
+on x=-60785..11593,y=-56135..2999,z=-95368..-26915
+on x=-32178..58085,y=17647..101866,z=-91405..-8878
+on x=-53655..12091,y=50097..105568,z=-75335..-4862
+on x=-111166..-40997,y=-71714..2688,z=5609..50954
+on x=-16602..70118,y=-98693..-44401,z=5197..76897
+on x=16383..101554,y=4615..83635,z=-44907..18747
+off x=-95822..-15171,y=-19987..48940,z=10804..104439
+on x=-89813..-14614,y=16069..88491,z=-3297..45228
+on x=41075..99376,y=-20427..49978,z=-52012..13762
+on x=-21330..50085,y=-17944..62733,z=-112280..-30197
+on x=-16478..35915,y=36008..118594,z=-7885..47086
+off x=-98156..-27851,y=-49952..43171,z=-99005..-8456
+off x=2032..69770,y=-71013..4824,z=7471..94418
+on x=43670..120875,y=-42068..12382,z=-24787..38892
+off x=37514..111226,y=-45862..25743,z=-16714..54663
+off x=25699..97951,y=-30668..59918,z=-15349..69697
+off x=-44271..17935,y=-9516..60759,z=49131..112598
+on x=-61695..-5813,y=40978..94975,z=8655..80240
+off x=-101086..-9439,y=-7088..67543,z=33935..83858
+off x=18020..114017,y=-48931..32606,z=21474..89843
+off x=-77139..10506,y=-89994..-18797,z=-80..59318
+off x=8476..79288,y=-75520..11602,z=-96624..-24783
+on x=-47488..-1262,y=24338..100707,z=16292..72967
+off x=-84341..13987,y=2429..92914,z=-90671..-1318
+off x=-37810..49457,y=-71013..-7894,z=-105357..-13188
+off x=-27365..46395,y=31009..98017,z=15428..76570
+off x=-70369..-16548,y=22648..78696,z=-1892..86821
+on x=-53470..21291,y=-120233..-33476,z=-44150..38147
+off x=-93533..-4276,y=-16170..68771,z=-104985..-24507
+"#,
+            part_1: Some("474140"),
+            part_2: Some("2758514936282235"),
+        },
+    ],
+}
+
+
+/// The cubes from -50 to 50 on each axis, which are all that the initialization procedure uses.
+pub const INITIALIZATION_REGION: Cuboid = Cuboid { x: (-50, 50), y: (-50, 50), z: (-50, 50) };
+
+/// The cubes between the minimum and maximum on each axis, both included.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Cuboid {
+    pub x: (i64, i64),
+    pub y: (i64, i64),
+    pub z: (i64, i64),
+}
+
+impl Cuboid {
+    pub fn volume(&self) -> i64 {
+        [self.x, self.y, self.z].iter().map(|(min, max)| max - min + 1).product()
+    }
+
+    /// The cubes in both cuboids, if there are any.
+    pub fn intersection(&self, other: &Cuboid) -> Option<Cuboid> {
+        let overlap = |(min_a, max_a): (i64, i64), (min_b, max_b): (i64, i64)| {
+            let (min, max) = (min_a.max(min_b), max_a.min(max_b));
+            if min <= max { Some((min, max)) } else { None }
+        };
+        Some(Cuboid {
+            x: overlap(self.x, other.x)?,
+            y: overlap(self.y, other.y)?,
+            z: overlap(self.z, other.z)?,
+        })
+    }
+}
+
+/// A reboot step, which turns every cube in the cuboid on or off.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Step {
+    pub on: bool,
+    pub cuboid: Cuboid,
+}
+
+/// The cubes that are on, kept as cuboids that each count their cubes once, positively or
+/// negatively. Adding a cuboid cancels out its overlap with every cuboid already counted, so that
+/// the cubes in the overlap are only counted as often as they were before, which is once if they
+/// were on. Turning cubes off is the same without adding the cuboid itself.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Reactor {
+    pub cuboids: HashMap<Cuboid, i64>,
+}
+
+impl Reactor {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn apply(&mut self, step: &Step) {
+        let mut changes = HashMap::new();
+        for (cuboid, sign) in self.cuboids.iter() {
+            if let Some(overlap) = cuboid.intersection(&step.cuboid) {
+                *changes.entry(overlap).or_insert(0) -= sign;
+            }
+        }
+        if step.on {
+            *changes.entry(step.cuboid).or_insert(0) += 1;
+        }
+        for (cuboid, change) in changes {
+            *self.cuboids.entry(cuboid).or_insert(0) += change;
+        }
+        self.cuboids.retain(|_, sign| *sign != 0);
+    }
+
+    /// How many cubes are on.
+    pub fn cubes_on(&self) -> i64 {
+        self.cuboids.iter().map(|(cuboid, sign)| cuboid.volume() * sign).sum()
+    }
+}
+
+/// Runs the steps and counts the cubes that are on afterwards, only looking at the cubes in
+/// `region` if it is given.
+pub fn reboot(steps: &[Step], region: Option<&Cuboid>) -> i64 {
+    let mut reactor = Reactor::new();
+    for step in steps {
+        let cuboid = match region {
+            Some(region) => match step.cuboid.intersection(region) {
+                Some(cuboid) => cuboid,
+                None => continue,
+            },
+            None => step.cuboid,
+        };
+        reactor.apply(&Step { on: step.on, cuboid });
+    }
+    reactor.cubes_on()
+}
+
+pub fn parse_steps(lines: &[String]) -> anyhow::Result<Vec<Step>> {
+    lines.iter().enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| parse_step(i, line))
+        .collect()
+}
+
+/// Parses a step such as `on x=10..12,y=10..12,z=10..12`.
+/// `line_index` is where the line is in the input, for reporting errors.
+pub fn parse_step(line_index: usize, line: &str) -> anyhow::Result<Step> {
+    let trimmed = line.trim();
+    let (state, ranges) = trimmed.split_once(' ')
+        .ok_or_else(|| error_at(line_index, column_of(line, trimmed), "expected 'on' or 'off' followed by the cuboid"))?;
+    let on = match state {
+        "on" => true,
+        "off" => false,
+        _ => return Err(error_at(line_index, column_of(line, state), format!("expected 'on' or 'off', found '{}'", state))),
+    };
+    let ranges = ranges.split(',').collect::<Vec<&str>>();
+    let (x, y, z) = match ranges.as_slice() {
+        [x, y, z] => (x, y, z),
+        _ => return Err(error_at(line_index, column_of(line, ranges[0]), format!("expected ranges for x, y and z separated by ',', found {}", ranges.len()))),
+    };
+    Ok(Step {
+        on,
+        cuboid: Cuboid {
+            x: parse_range(line_index, line, x, "x")?,
+            y: parse_range(line_index, line, y, "y")?,
+            z: parse_range(line_index, line, z, "z")?,
+        },
+    })
+}
+
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+    use crate::day_22::*;
+
+    fn get_test_input(example: usize) -> Vec<Step> {
+        parse_steps(PuzzleInput::from(DAY.examples[example].input).lines()).unwrap()
+    }
+
+    /// Turns the cubes on and off one at a time, for cuboids small enough to do so.
+    fn brute_force(steps: &[Step]) -> usize {
+        let mut cubes = HashSet::new();
+        for step in steps {
+            let Cuboid { x, y, z } = step.cuboid;
+            for cube in (x.0..=x.1).flat_map(|i| (y.0..=y.1).flat_map(move |j| (z.0..=z.1).map(move |k| (i, j, k)))) {
+                if step.on {
+                    cubes.insert(cube);
+                } else {
+                    cubes.remove(&cube);
+                }
+            }
+        }
+        cubes.len()
+    }
+
+    #[test]
+    fn test_cuboid() {
+        let a = Cuboid { x: (10, 12), y: (10, 12), z: (10, 12) };
+        let b = Cuboid { x: (11, 13), y: (11, 13), z: (11, 13) };
+        assert_eq!(a.volume(), 27);
+        assert_eq!(a.intersection(&b), Some(Cuboid { x: (11, 12), y: (11, 12), z: (11, 12) }));
+        assert_eq!(a.intersection(&Cuboid { x: (13, 13), y: (10, 12), z: (10, 12) }), None);
+    }
+
+    #[test]
+    fn test_reboot() {
+        let steps = get_test_input(0);
+        let mut reactor = Reactor::new();
+        let counts = steps.iter().map(|step| {
+            reactor.apply(step);
+            reactor.cubes_on()
+        }).collect::<Vec<i64>>();
+        assert_eq!(counts, [27, 46, 38, 39]);
+        assert_eq!(reboot(&get_test_input(1), Some(&INITIALIZATION_REGION)), 590784);
+        let steps = get_test_input(2);
+        assert_eq!(reboot(&steps, Some(&INITIALIZATION_REGION)), 474140);
+        assert_eq!(reboot(&steps, None), 2758514936282235);
+
+        // Overlapping steps, some of them partly outside the initialization region.
+        let steps = parse_steps(&[
+            "on x=-3..4,y=-2..2,z=0..5", "on x=0..6,y=-4..1,z=-2..3", "off x=1..2,y=-5..5,z=1..1",
+            "on x=-1..1,y=-1..1,z=-1..1", "off x=-10..10,y=2..2,z=-10..10", "on x=2..8,y=0..3,z=2..2",
+            "on x=45..55,y=-55..-45,z=0..3",
+        ].iter().map(|line| line.to_string()).collect::<Vec<String>>()).unwrap();
+        assert_eq!(reboot(&steps, None) as usize, brute_force(&steps));
+        let clipped = steps.iter().filter_map(|step| step.cuboid.intersection(&INITIALIZATION_REGION).map(|cuboid| Step { on: step.on, cuboid }))
+            .collect::<Vec<Step>>();
+        assert_eq!(reboot(&steps, Some(&INITIALIZATION_REGION)) as usize, brute_force(&clipped));
+        assert_eq!(reboot(&steps, None) - reboot(&steps, Some(&INITIALIZATION_REGION)), 11 * 11 * 4 - 6 * 6 * 4);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let parse = |s: &str| parse_step(0, s).unwrap_err().to_string();
+        assert_eq!(parse("on"), "line 1, column 1: expected 'on' or 'off' followed by the cuboid");
+        assert_eq!(parse("toggle x=1..2,y=1..2,z=1..2"), "line 1, column 1: expected 'on' or 'off', found 'toggle'");
+        assert_eq!(parse("on x=1..2,y=1..2"), "line 1, column 4: expected ranges for x, y and z separated by ',', found 2");
+        assert_eq!(parse("off x=1..2,z=1..2,y=1..2"), "line 1, column 12: expected the range to start with 'y='");
+        assert_eq!(parse("on x=1..2,y=1..2,z=1.2"), "line 1, column 20: expected the bounds to be separated by '..'");
+        assert_eq!(parse("on x=1..2,y=a..2,z=1..2"), "line 1, column 13: could not parse 'a': invalid digit found in string");
+        assert_eq!(parse("on x=3..2,y=1..2,z=1..2"), "line 1, column 6: the x range is empty");
+    }
+}
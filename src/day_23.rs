@@ -0,0 +1,423 @@
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+use std::fmt;
+use crate::input::PuzzleInput;
+use crate::registry::Example;
+use crate::solution::{Answer, Solution};
+use crate::utils::error_at;
+
+
+/// Day 23: Amphipod
+/// https://adventofcode.com/2021/day/23
+pub struct Day23;
+
+impl Solution for Day23 {
+    type Input = Burrow;
+
+    fn parse(input: &PuzzleInput) -> anyhow::Result<Self::Input> {
+        parse_burrow(input.lines())
+    }
+
+    fn part_1(burrow: &Self::Input) -> anyhow::Result<Answer> {
+        organisation_answer(burrow)
+    }
+
+    fn part_2(burrow: &Self::Input) -> anyhow::Result<Answer> {
+        organisation_answer(&burrow.unfold()?)
+    }
+}
+
+register_day! {
+    day: 23,
+    title: "Amphipod",
+    solution: Day23,
+    parts: [1, 2],
+    examples: [
+        Example {
+            input: r#"#############
+#...........#
+###B#C#B#D###
+  #A#D#C#A#
+  #########
+"#,
+            part_1: Some("12521"),
+            part_2: Some("44169"),
+        },
+    ],
+}
+
+fn organisation_answer(burrow: &Burrow) -> anyhow::Result<Answer> {
+    let organisation = organise(burrow).ok_or_else(|| anyhow::anyhow!("The amphipods cannot be organised"))?;
+    Ok(Answer::from(organisation.energy).with_details(vec![
+        ("moves", organisation.steps.len().into()),
+    ]))
+}
+
+
+const HALLWAY_LENGTH: usize = 11;
+const ROOM_COUNT: usize = 4;
+const MAX_DEPTH: usize = 4;
+
+/// The rows found in the unfolded diagram, which go between the two rows of the folded one.
+const UNFOLDED_ROWS: [[Amphipod; ROOM_COUNT]; 2] = [
+    [Amphipod::Desert, Amphipod::Copper, Amphipod::Bronze, Amphipod::Amber],
+    [Amphipod::Desert, Amphipod::Bronze, Amphipod::Amber, Amphipod::Copper],
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Amphipod {
+    Amber,
+    Bronze,
+    Copper,
+    Desert,
+}
+
+impl Amphipod {
+    pub fn from_char(c: char) -> Option<Amphipod> {
+        match c {
+            'A' => Some(Amphipod::Amber),
+            'B' => Some(Amphipod::Bronze),
+            'C' => Some(Amphipod::Copper),
+            'D' => Some(Amphipod::Desert),
+            _ => None,
+        }
+    }
+
+    pub fn to_char(self) -> char {
+        match self {
+            Amphipod::Amber => 'A',
+            Amphipod::Bronze => 'B',
+            Amphipod::Copper => 'C',
+            Amphipod::Desert => 'D',
+        }
+    }
+
+    /// The energy it takes to move one step.
+    pub fn energy(self) -> u64 {
+        match self {
+            Amphipod::Amber => 1,
+            Amphipod::Bronze => 10,
+            Amphipod::Copper => 100,
+            Amphipod::Desert => 1000,
+        }
+    }
+
+    /// The room it belongs in, counting from the left.
+    pub fn room(self) -> usize {
+        self as usize
+    }
+
+    /// The amphipod that belongs in `room`.
+    fn from_index(room: usize) -> Amphipod {
+        [Amphipod::Amber, Amphipod::Bronze, Amphipod::Copper, Amphipod::Desert][room]
+    }
+}
+
+/// Where an amphipod is, as a space of the hallway counting from the left, or a room counting
+/// from the left and how far down it the amphipod is, counting from the top.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Location {
+    Hallway(usize),
+    Room(usize, usize),
+}
+
+impl fmt::Display for Location {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Location::Hallway(space) => write!(f, "hallway space {}", space + 1),
+            Location::Room(room, depth) => write!(f, "room {} space {}", room + 1, depth + 1),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Move {
+    pub amphipod: Amphipod,
+    pub from: Location,
+    pub to: Location,
+    pub energy: u64,
+}
+
+impl fmt::Display for Move {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} moves from {} to {}, using {} energy", self.amphipod.to_char(), self.from, self.to, self.energy)
+    }
+}
+
+/// The hallway space outside `room`, where amphipods can't stop.
+fn entrance(room: usize) -> usize {
+    2 + 2 * room
+}
+
+fn is_entrance(space: usize) -> bool {
+    (0..ROOM_COUNT).any(|room| entrance(room) == space)
+}
+
+/// Every space in the burrow, with the rooms `depth` spaces deep. Rooms are stored at the
+/// largest depth so that burrows can be copied and hashed cheaply, with only the first `depth`
+/// spaces of each used.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Burrow {
+    pub hallway: [Option<Amphipod>; HALLWAY_LENGTH],
+    pub rooms: [[Option<Amphipod>; MAX_DEPTH]; ROOM_COUNT],
+    pub depth: usize,
+}
+
+impl Burrow {
+    /// Whether every amphipod is in its own room.
+    pub fn is_organised(&self) -> bool {
+        (0..ROOM_COUNT).all(|room| self.rooms[room][..self.depth].iter().all(|space| *space == Some(Amphipod::from_index(room))))
+    }
+
+    /// Whether `room` only has amphipods that belong in it, so others don't need to leave it.
+    fn room_is_ready(&self, room: usize) -> bool {
+        self.rooms[room][..self.depth].iter().all(|space| space.is_none_or(|amphipod| amphipod.room() == room))
+    }
+
+    /// Whether the hallway is empty going from `from` to `to`, not counting `from` itself.
+    fn hallway_is_clear(&self, from: usize, to: usize) -> bool {
+        let spaces = if from < to { from + 1..to + 1 } else { to..from };
+        self.hallway[spaces].iter().all(Option::is_none)
+    }
+
+    fn apply(&self, amphipod: Amphipod, from: Location, to: Location, steps: usize) -> (Move, Burrow) {
+        let mut burrow = *self;
+        for (location, value) in [(from, None), (to, Some(amphipod))].iter().copied() {
+            match location {
+                Location::Hallway(space) => burrow.hallway[space] = value,
+                Location::Room(room, depth) => burrow.rooms[room][depth] = value,
+            }
+        }
+        (Move { amphipod, from, to, energy: steps as u64 * amphipod.energy() }, burrow)
+    }
+
+    /// Every legal move, with the burrow after it. Amphipods in the hallway can only move into
+    /// their own room, once it only has their kind in it, and go as deep as they can. Amphipods
+    /// in rooms they need to leave can move to any hallway space they can reach that isn't an
+    /// entrance. Moving an amphipod into its room never makes things worse, as it always costs
+    /// the same and only gets it out of the way, so when one can only that move is given.
+    pub fn moves(&self) -> Vec<(Move, Burrow)> {
+        for (space, amphipod) in self.hallway.iter().enumerate() {
+            let amphipod = match amphipod {
+                Some(amphipod) => *amphipod,
+                None => continue,
+            };
+            let room = amphipod.room();
+            if self.room_is_ready(room) && self.hallway_is_clear(space, entrance(room)) {
+                let depth = self.rooms[room][..self.depth].iter().rposition(Option::is_none)
+                    .expect("a ready room has space while one of its amphipods is in the hallway");
+                let steps = space.max(entrance(room)) - space.min(entrance(room)) + depth + 1;
+                return vec![self.apply(amphipod, Location::Hallway(space), Location::Room(room, depth), steps)];
+            }
+        }
+
+        let mut moves = vec![];
+        for room in (0..ROOM_COUNT).filter(|room| !self.room_is_ready(*room)) {
+            let depth = self.rooms[room][..self.depth].iter().position(Option::is_some)
+                .expect("a room that isn't ready has an amphipod in it");
+            let amphipod = self.rooms[room][depth].unwrap();
+            for space in (0..HALLWAY_LENGTH).filter(|space| !is_entrance(*space)) {
+                if self.hallway_is_clear(entrance(room), space) {
+                    let steps = depth + 1 + space.max(entrance(room)) - space.min(entrance(room));
+                    moves.push(self.apply(amphipod, Location::Room(room, depth), Location::Hallway(space), steps));
+                }
+            }
+        }
+        moves
+    }
+
+    /// The burrow from the full diagram, with the two extra rows inserted into each room.
+    pub fn unfold(&self) -> anyhow::Result<Burrow> {
+        if self.depth != 2 {
+            return Err(anyhow::anyhow!("Only burrows with rooms 2 deep can be unfolded, found {}", self.depth));
+        }
+        let mut burrow = *self;
+        burrow.depth = 4;
+        for (room, spaces) in burrow.rooms.iter_mut().enumerate() {
+            *spaces = [self.rooms[room][0], Some(UNFOLDED_ROWS[0][room]), Some(UNFOLDED_ROWS[1][room]), self.rooms[room][1]];
+        }
+        Ok(burrow)
+    }
+}
+
+impl fmt::Display for Burrow {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let to_char = |space: &Option<Amphipod>| space.map_or('.', Amphipod::to_char);
+        writeln!(f, "#############")?;
+        writeln!(f, "#{}#", self.hallway.iter().map(to_char).collect::<String>())?;
+        for depth in 0..self.depth {
+            let row = self.rooms.iter().map(|room| to_char(&room[depth]).to_string()).collect::<Vec<String>>().join("#");
+            if depth == 0 {
+                writeln!(f, "###{}###", row)?;
+            } else {
+                writeln!(f, "  #{}#", row)?;
+            }
+        }
+        write!(f, "  #########")
+    }
+}
+
+/// The cheapest way of organising the amphipods.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Organisation {
+    pub start: Burrow,
+    pub energy: u64,
+    /// Each move, with the burrow after it.
+    pub steps: Vec<(Move, Burrow)>,
+}
+
+impl fmt::Display for Organisation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "{}", self.start)?;
+        for (amphipod_move, burrow) in self.steps.iter() {
+            writeln!(f)?;
+            writeln!(f, "{}", amphipod_move)?;
+            writeln!(f, "{}", burrow)?;
+        }
+        writeln!(f)?;
+        write!(f, "Total energy: {}", self.energy)
+    }
+}
+
+/// Runs Dijkstra's algorithm over the states of the burrow, from `start` to any organised state,
+/// returning the moves on the way there. Returns `None` if the amphipods can't be organised.
+pub fn organise(start: &Burrow) -> Option<Organisation> {
+    let mut energies = HashMap::new();
+    let mut previous: HashMap<Burrow, (Move, Burrow)> = HashMap::new();
+    let mut queue = BinaryHeap::new();
+    energies.insert(*start, 0);
+    queue.push(Reverse((0, *start)));
+
+    while let Some(Reverse((energy, burrow))) = queue.pop() {
+        if energies.get(&burrow).is_some_and(|best| *best < energy) {
+            continue;
+        }
+        if burrow.is_organised() {
+            let mut steps = vec![];
+            let mut current = burrow;
+            while let Some((amphipod_move, before)) = previous.get(&current) {
+                steps.push((*amphipod_move, current));
+                current = *before;
+            }
+            steps.reverse();
+            return Some(Organisation { start: *start, energy, steps });
+        }
+        for (amphipod_move, next) in burrow.moves() {
+            let next_energy = energy + amphipod_move.energy;
+            if energies.get(&next).is_none_or(|best| next_energy < *best) {
+                energies.insert(next, next_energy);
+                previous.insert(next, (amphipod_move, burrow));
+                queue.push(Reverse((next_energy, next)));
+            }
+        }
+    }
+    None
+}
+
+/// Parses the diagram of the burrow, which has from 1 to 4 rows of rooms. Spaces may be empty,
+/// but there must be as many of each amphipod as each room has spaces, and the amphipods in a
+/// room must be at the bottom of it.
+pub fn parse_burrow(lines: &[String]) -> anyhow::Result<Burrow> {
+    let lines = lines.iter().enumerate().filter(|(_, line)| !line.trim().is_empty()).collect::<Vec<(usize, &String)>>();
+    if lines.len() < 4 {
+        return Err(anyhow::anyhow!("Expected a diagram of the burrow, found {} lines", lines.len()));
+    }
+    let parse_space = |(i, line): (usize, &String), column: usize| match line.chars().nth(column) {
+        Some('.') => Ok(None),
+        Some(c) => Amphipod::from_char(c).map(Some).ok_or_else(|| error_at(i, column, format!("expected an amphipod or '.', found '{}'", c))),
+        None => Err(error_at(i, column, "expected an amphipod or '.', found the end of the line")),
+    };
+
+    let mut hallway = [None; HALLWAY_LENGTH];
+    for (space, value) in hallway.iter_mut().enumerate() {
+        *value = parse_space(lines[1], space + 1)?;
+    }
+    let room_lines = &lines[2..lines.len() - 1];
+    if room_lines.len() > MAX_DEPTH {
+        return Err(error_at(room_lines[MAX_DEPTH].0, 0, format!("expected at most {} rows of rooms", MAX_DEPTH)));
+    }
+    let mut rooms = [[None; MAX_DEPTH]; ROOM_COUNT];
+    for (depth, room_line) in room_lines.iter().enumerate() {
+        for (room, spaces) in rooms.iter_mut().enumerate() {
+            spaces[depth] = parse_space(*room_line, entrance(room) + 1)?;
+            if depth > 0 && spaces[depth].is_none() && spaces[depth - 1].is_some() {
+                return Err(error_at(room_line.0, entrance(room) + 1, "expected the amphipods in a room to be at the bottom of it"));
+            }
+        }
+    }
+
+    let burrow = Burrow { hallway, rooms, depth: room_lines.len() };
+    for amphipod in (0..ROOM_COUNT).map(Amphipod::from_index) {
+        let count = burrow.hallway.iter().chain(burrow.rooms.iter().flatten()).filter(|space| **space == Some(amphipod)).count();
+        if count != burrow.depth {
+            return Err(anyhow::anyhow!("Expected {} of amphipod {}, found {}", burrow.depth, amphipod.to_char(), count));
+        }
+    }
+    Ok(burrow)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use crate::day_23::*;
+
+    fn get_test_input(s: &str) -> Burrow {
+        parse_burrow(PuzzleInput::from(s).lines()).unwrap()
+    }
+
+    #[test]
+    fn test_organise() {
+        let burrow = get_test_input(DAY.examples[0].input);
+        assert_eq!(burrow.to_string(), DAY.examples[0].input.trim_end());
+        let organisation = organise(&burrow).unwrap();
+        assert_eq!(organisation.energy, 12521);
+        assert_eq!(organisation.steps.iter().map(|(amphipod_move, _)| amphipod_move.energy).sum::<u64>(), 12521);
+        assert!(organisation.steps.last().unwrap().1.is_organised());
+        let mut current = burrow;
+        for step in organisation.steps.iter() {
+            assert!(current.moves().contains(step));
+            current = step.1;
+        }
+
+        let unfolded = burrow.unfold().unwrap();
+        assert_eq!(unfolded.to_string(), r#"#############
+#...........#
+###B#C#B#D###
+  #D#C#B#A#
+  #D#B#A#C#
+  #A#D#C#A#
+  #########"#);
+        assert_eq!(organise(&unfolded).unwrap().energy, 44169);
+    }
+
+    #[test]
+    fn test_small_burrows() {
+        let organised = get_test_input("#############\n#...........#\n###A#B#C#D###\n  #########");
+        assert_eq!(organise(&organised).unwrap(), Organisation { start: organised, energy: 0, steps: vec![] });
+        // Whichever of them leaves its room first has to wait in the hallway for the other to get past.
+        let swapped = get_test_input("#############\n#...........#\n###B#A#C#D###\n  #A#B#C#D#\n  #########");
+        let organisation = organise(&swapped).unwrap();
+        assert_eq!(organisation.energy, 46);
+        assert!(organisation.steps.iter().any(|(amphipod_move, _)| *amphipod_move == Move { amphipod: Amphipod::Bronze, from: Location::Room(0, 0), to: Location::Hallway(3), energy: 20 }));
+        let printed = organisation.to_string();
+        assert!(printed.starts_with(&format!("{}\n\n", swapped)));
+        assert!(printed.ends_with("\n#############\n#...........#\n###A#B#C#D###\n  #A#B#C#D#\n  #########\n\nTotal energy: 46"));
+        assert_eq!(printed.matches(" moves from ").count(), organisation.steps.len());
+        // Partway through, with amphipods already in the hallway.
+        let partway = get_test_input("#############\n#.....D.D.A.#\n###.#B#C#.###\n  #A#B#C#.#\n  #########");
+        assert_eq!(organise(&partway).unwrap().energy, 7008);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let parse = |s: &str| parse_burrow(PuzzleInput::from(s).lines()).unwrap_err().to_string();
+        assert_eq!(parse("#############\n#...........#\n  #########"), "Expected a diagram of the burrow, found 3 lines");
+        assert_eq!(parse("#############\n#....E......#\n###A#B#C#D###\n  #########"), "line 2, column 6: expected an amphipod or '.', found 'E'");
+        assert_eq!(parse("#############\n#...........#\n###A#B#C#D###\n  #A#B#\n  #########"), "line 4, column 8: expected an amphipod or '.', found the end of the line");
+        assert_eq!(parse("#############\n#A..........#\n###A#B#C#D###\n  #.#B#C#D#\n  #########"), "line 4, column 4: expected the amphipods in a room to be at the bottom of it");
+        assert_eq!(parse("#############\n#...........#\n###A#B#C#D###\n  #A#B#C#C#\n  #########"), "Expected 2 of amphipod C, found 3");
+        let deep = format!("#############\n#...........#\n###A#B#C#D###\n{}  #########", "  #A#B#C#D#\n".repeat(4));
+        assert_eq!(parse(&deep), "line 7, column 1: expected at most 4 rows of rooms");
+        assert_eq!(get_test_input(&deep.replacen("  #A#B#C#D#\n", "", 1)).unfold().unwrap_err().to_string(), "Only burrows with rooms 2 deep can be unfolded, found 4");
+    }
+}
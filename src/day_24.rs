@@ -1,32 +1,646 @@
-pub fn part_1(_: &Vec<String>) {
-    //                       0   1   2    3   4   5    6   7   8   9  10  11  12  13
-    let model_number = vec![ 9,  9,  9,   1,  9,  7,   6,  5,  9,  4,  9,  4,  9,  8];
-    check_model_number(&model_number);
+use std::collections::HashSet;
+use std::fmt;
+use std::iter;
+use crate::input::PuzzleInput;
+use crate::solution::{Answer, Solution};
+use crate::utils::{column_of, error_at, parse_at};
+
+mod symbolic;
+pub use symbolic::{eliminate_dead_stores, fold, pseudo_code, register_intervals, run_statements, simplify, Expr, Interval, Op, Statement};
+
+
+/// Day 24: Arithmetic Logic Unit
+/// https://adventofcode.com/2021/day/24
+pub struct Day24;
+
+impl Solution for Day24 {
+    type Input = Vec<Instruction>;
+
+    fn parse(input: &PuzzleInput) -> anyhow::Result<Self::Input> {
+        parse_program(input.lines())
+    }
+
+    fn part_1(program: &Self::Input) -> anyhow::Result<Answer> {
+        let model_number = find_model_number_or_search(program, Extreme::Largest)?;
+        checked_model_number(program, &model_number)
+    }
+
+    fn part_2(program: &Self::Input) -> anyhow::Result<Answer> {
+        let model_number = find_model_number_or_search(program, Extreme::Smallest)?;
+        checked_model_number(program, &model_number)
+    }
+}
+
+register_day! {
+    day: 24,
+    title: "Arithmetic Logic Unit",
+    solution: Day24,
+    parts: [1, 2],
+    examples: [],
+}
+
+
+/// One of the ALU's four registers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Register {
+    W,
+    X,
+    Y,
+    Z,
+}
+
+impl Register {
+    pub const ALL: [Register; 4] = [Register::W, Register::X, Register::Y, Register::Z];
+
+    fn index(self) -> usize {
+        self as usize
+    }
+}
+
+impl fmt::Display for Register {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Register::W => 'w',
+            Register::X => 'x',
+            Register::Y => 'y',
+            Register::Z => 'z',
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// The second argument of an instruction, which is either a register or a number.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Operand {
+    Register(Register),
+    Value(i64),
+}
+
+impl fmt::Display for Operand {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Operand::Register(register) => write!(f, "{}", register),
+            Operand::Value(value) => write!(f, "{}", value),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Instruction {
+    /// Reads the next input digit into the register.
+    Inp(Register),
+    Add(Register, Operand),
+    Mul(Register, Operand),
+    /// Divides, rounding towards zero.
+    Div(Register, Operand),
+    Mod(Register, Operand),
+    /// Sets the register to 1 if both arguments are equal, otherwise 0.
+    Eql(Register, Operand),
+}
+
+impl fmt::Display for Instruction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Instruction::Inp(a) => write!(f, "inp {}", a),
+            Instruction::Add(a, b) => write!(f, "add {} {}", a, b),
+            Instruction::Mul(a, b) => write!(f, "mul {} {}", a, b),
+            Instruction::Div(a, b) => write!(f, "div {} {}", a, b),
+            Instruction::Mod(a, b) => write!(f, "mod {} {}", a, b),
+            Instruction::Eql(a, b) => write!(f, "eql {} {}", a, b),
+        }
+    }
+}
+
+/// Parses the program, one instruction per non-empty line.
+pub fn parse_program(lines: &[String]) -> anyhow::Result<Vec<Instruction>> {
+    let program = lines.iter().enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| parse_instruction(i, line))
+        .collect::<anyhow::Result<Vec<Instruction>>>()?;
+    if program.is_empty() {
+        return Err(anyhow::anyhow!("The input is empty"));
+    }
+    Ok(program)
+}
+
+/// Parses an instruction such as `add x -3`. `line_index` is where the line is in the input, for
+/// reporting errors.
+pub fn parse_instruction(line_index: usize, line: &str) -> anyhow::Result<Instruction> {
+    let parts = line.split_whitespace().collect::<Vec<&str>>();
+    let register = |piece: &str| match piece {
+        "w" => Ok(Register::W),
+        "x" => Ok(Register::X),
+        "y" => Ok(Register::Y),
+        "z" => Ok(Register::Z),
+        _ => Err(error_at(line_index, column_of(line, piece), format!("expected a register from w to z, found '{}'", piece))),
+    };
+    let operand = |piece: &str| match register(piece) {
+        Ok(register) => Ok(Operand::Register(register)),
+        Err(_) => parse_at::<i64>(line_index, line, piece).map(Operand::Value),
+    };
+
+    if parts.is_empty() {
+        return Err(error_at(line_index, 0, "expected an instruction"));
+    }
+    let expected_arguments = if parts[0] == "inp" { 1 } else { 2 };
+    if parts.len() != expected_arguments + 1 {
+        return Err(error_at(line_index, column_of(line, parts[0]), format!("expected '{}' to have {} arguments, found {}", parts[0], expected_arguments, parts.len() - 1)));
+    }
+    Ok(match parts[0] {
+        "inp" => Instruction::Inp(register(parts[1])?),
+        "add" => Instruction::Add(register(parts[1])?, operand(parts[2])?),
+        "mul" => Instruction::Mul(register(parts[1])?, operand(parts[2])?),
+        "div" => Instruction::Div(register(parts[1])?, operand(parts[2])?),
+        "mod" => Instruction::Mod(register(parts[1])?, operand(parts[2])?),
+        "eql" => Instruction::Eql(register(parts[1])?, operand(parts[2])?),
+        other => return Err(error_at(line_index, column_of(line, other), format!("unknown instruction '{}'", other))),
+    })
+}
+
+/// The state of the ALU, which starts with every register at 0.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Alu {
+    registers: [i64; 4],
+}
+
+impl Alu {
+    pub fn get(&self, register: Register) -> i64 {
+        self.registers[register.index()]
+    }
+
+    pub fn set(&mut self, register: Register, value: i64) {
+        self.registers[register.index()] = value;
+    }
+
+    fn value(&self, operand: Operand) -> i64 {
+        match operand {
+            Operand::Register(register) => self.get(register),
+            Operand::Value(value) => value,
+        }
+    }
+
+    /// Runs a single instruction, taking the next digit from `input` if it needs one.
+    /// Fails on the operations the puzzle says would crash the ALU: dividing by zero, and taking
+    /// the modulo of a negative number or by a number that is not positive. Also fails if a result
+    /// doesn't fit in 64 bits.
+    pub fn step(&mut self, instruction: &Instruction, input: &mut impl Iterator<Item = i64>) -> anyhow::Result<()> {
+        let overflow = |(a, b): (i64, i64), op: &str| anyhow::anyhow!("{} {} {} does not fit in 64 bits", a, op, b);
+        let (register, result) = match *instruction {
+            Instruction::Inp(a) => (a, input.next().ok_or_else(|| anyhow::anyhow!("ran out of input"))?),
+            Instruction::Add(a, b) => {
+                let values = (self.get(a), self.value(b));
+                (a, values.0.checked_add(values.1).ok_or_else(|| overflow(values, "+"))?)
+            },
+            Instruction::Mul(a, b) => {
+                let values = (self.get(a), self.value(b));
+                (a, values.0.checked_mul(values.1).ok_or_else(|| overflow(values, "*"))?)
+            },
+            Instruction::Div(a, b) => {
+                let values = (self.get(a), self.value(b));
+                if values.1 == 0 {
+                    return Err(anyhow::anyhow!("division by zero"));
+                }
+                (a, values.0.checked_div(values.1).ok_or_else(|| overflow(values, "/"))?)
+            },
+            Instruction::Mod(a, b) => {
+                let (dividend, divisor) = (self.get(a), self.value(b));
+                if dividend < 0 || divisor <= 0 {
+                    return Err(anyhow::anyhow!("modulo of {} by {}", dividend, divisor));
+                }
+                (a, dividend % divisor)
+            },
+            Instruction::Eql(a, b) => (a, (self.get(a) == self.value(b)) as i64),
+        };
+        self.set(register, result);
+        Ok(())
+    }
+
+    /// Runs `program` from the start, reading `inp` instructions from `input` in order.
+    pub fn run(program: &[Instruction], input: impl IntoIterator<Item = i64>) -> anyhow::Result<Alu> {
+        let mut alu = Alu::default();
+        let mut input = input.into_iter();
+        for (i, instruction) in program.iter().enumerate() {
+            alu.step(instruction, &mut input)
+                .map_err(|e| anyhow::anyhow!("Instruction {} ({}) failed: {}", i + 1, instruction, e))?;
+        }
+        Ok(alu)
+    }
+}
+
+/// Runs the MONAD program on the model number and returns the final value of z, which is 0 if the
+/// model number is valid.
+pub fn check_model_number(program: &[Instruction], model_number: &[i64]) -> anyhow::Result<i64> {
+    Ok(Alu::run(program, model_number.iter().copied())?.get(Register::Z))
+}
+
+/// The parameters that differ between the blocks of MONAD, one block per digit:
+///
+/// ```text
+/// inp w      mul y 0
+/// mul x 0    add y 25
+/// add x z    mul y x
+/// mod x 26   add y 1
+/// div z k    mul z y
+/// add x a    mul y 0
+/// eql x w    add y w
+/// eql x 0    add y b
+///            mul y x
+///            add z y
+/// ```
+///
+/// z is used as a stack of base 26 numbers. Each block checks whether the digit equals the top of
+/// the stack plus `a`, pops the stack if `k` is 26, and pushes the digit plus `b` if the check
+/// failed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Block {
+    pub a: i64,
+    pub b: i64,
+    pub k: i64,
+}
+
+/// Instructions of a block that are the same in every block, by their position in the block.
+const BLOCK_TEMPLATE: [&str; 18] = [
+    "inp w", "mul x 0", "add x z", "mod x 26", "div z k", "add x a", "eql x w", "eql x 0", "mul y 0",
+    "add y 25", "mul y x", "add y 1", "mul z y", "mul y 0", "add y w", "add y b", "mul y x", "add z y",
+];
+
+/// Splits MONAD into its blocks and reads each one's parameters, failing if the program is not
+/// made of blocks like the one `Block` describes.
+pub fn extract_blocks(program: &[Instruction]) -> anyhow::Result<Vec<Block>> {
+    if !program.len().is_multiple_of(BLOCK_TEMPLATE.len()) {
+        return Err(anyhow::anyhow!("Expected MONAD to be made of blocks of {} instructions, found {} instructions", BLOCK_TEMPLATE.len(), program.len()));
+    }
+    program.chunks(BLOCK_TEMPLATE.len()).enumerate().map(|(i, block)| {
+        let mut parameters = [0; 3];
+        for (j, (instruction, expected)) in block.iter().zip(BLOCK_TEMPLATE.iter()).enumerate() {
+            // Parameters are the last character of their line in the template, and match any number.
+            let text = instruction.to_string();
+            let matches = match ['a', 'b', 'k'].iter().position(|name| expected.ends_with(*name)) {
+                Some(p) => text.strip_prefix(&expected[..expected.len() - 1])
+                    .and_then(|value| value.parse::<i64>().ok())
+                    .map(|value| parameters[p] = value)
+                    .is_some(),
+                None => text == *expected,
+            };
+            if !matches {
+                return Err(anyhow::anyhow!("Expected instruction {} of block {} to be '{}', found '{}'", j + 1, i + 1, expected, instruction));
+            }
+        }
+        let [a, b, k] = parameters;
+        Ok(Block { a, b, k })
+    }).collect()
 }
 
-pub fn part_2(_: &Vec<String>) {
-    //                       0   1   2    3   4   5    6   7   8   9  10  11  12  13
-    let model_number = vec![ 2,  4,  9,   1,  3,  1,   1,  1,  6,  1,  6,  1,  5,  1];
-    check_model_number(&model_number);
+/// Two digits that have to differ by a fixed amount for z to end up at 0:
+/// `digit[pop] == digit[push] + offset`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Constraint {
+    pub push: usize,
+    pub pop: usize,
+    pub offset: i64,
 }
 
+/// Pairs up the blocks that push a digit onto z with the blocks that pop it off again.
+/// A pushing block can never pass its check, as `a` is more than 9, so the only way z can end up
+/// back at 0 is if every popping block passes its check and so does not push.
+pub fn find_constraints(blocks: &[Block]) -> anyhow::Result<Vec<Constraint>> {
+    let mut stack = vec![];
+    let mut constraints = vec![];
+    for (i, block) in blocks.iter().enumerate() {
+        match block.k {
+            1 if block.a > 9 => stack.push(i),
+            1 => return Err(anyhow::anyhow!("Block {} does not pop z, but could pass its check as a is {}", i + 1, block.a)),
+            26 => {
+                let push = stack.pop().ok_or_else(|| anyhow::anyhow!("Block {} pops z when nothing has been pushed", i + 1))?;
+                constraints.push(Constraint { push, pop: i, offset: blocks[push].b + block.a });
+            },
+            k => return Err(anyhow::anyhow!("Block {} divides z by {}, expected 1 or 26", i + 1, k)),
+        }
+    }
+    if !stack.is_empty() {
+        return Err(anyhow::anyhow!("{} digits are pushed onto z but never popped, so z can never be 0", stack.len()));
+    }
+    Ok(constraints)
+}
 
-fn check_model_number(model_number: &Vec<i64>) {
-    //                   0   1   2    3   4   5    6   7   8   9  10  11  12  13
-    let a_lookup = vec![14, 15, 13, -10, 14, -3, -14, 12, 14, 12, -6, -6, -2, -9];
-    let b_lookup = vec![ 8, 11,  2,  11,  1,  5,  10,  6,  1, 11,  9, 14, 11,  2];
-    let k_lookup = vec![ 1,  1,  1,  26,  1, 26,  26,  1,  1,  1, 26, 26, 26, 26];
-    let mut z = 0;
-    for (i, digit) in model_number.iter().enumerate() {
-        let a = a_lookup[i];
-        let b = b_lookup[i];
-        println!("Current: {} {} {} {} {} {}", i, digit, a, b, z, z % 26);
-        let cond = (z % 26) + a == *digit;
-        z /= k_lookup[i];
-        if !cond {
-            z = (z * 26) + digit + b;
-            println!("Adding layer {} with {}, new z {}", i, digit + b, z);
+/// Which valid model number to find.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Extreme {
+    Largest,
+    Smallest,
+}
+
+/// Picks the digits for each constraint to make the largest or smallest model number, with every
+/// digit from 1 to 9.
+pub fn model_number_from_constraints(constraints: &[Constraint], digits: usize, extreme: Extreme) -> anyhow::Result<Vec<i64>> {
+    let mut model_number = vec![0; digits];
+    for constraint in constraints {
+        // The pushed digit comes first, so it decides which model number is larger.
+        let push_digit = match extreme {
+            Extreme::Largest => 9.min(9 - constraint.offset),
+            Extreme::Smallest => 1.max(1 - constraint.offset),
+        };
+        let pop_digit = push_digit + constraint.offset;
+        if !(1..=9).contains(&push_digit) || !(1..=9).contains(&pop_digit) {
+            return Err(anyhow::anyhow!("No digits from 1 to 9 differ by {}, as digits {} and {} need to", constraint.offset, constraint.push + 1, constraint.pop + 1));
         }
+        model_number[constraint.push] = push_digit;
+        model_number[constraint.pop] = pop_digit;
+    }
+    Ok(model_number)
+}
+
+/// Works out the largest or smallest model number MONAD accepts from the program itself.
+pub fn find_model_number(program: &[Instruction], extreme: Extreme) -> anyhow::Result<Vec<i64>> {
+    let blocks = extract_blocks(program)?;
+    let constraints = find_constraints(&blocks)?;
+    model_number_from_constraints(&constraints, blocks.len(), extreme)
+}
+
+/// Works out the model number from the constraints between digits if MONAD is made of the usual
+/// blocks, and otherwise searches for it, showing how far the search has got on stderr as it can
+/// take a while.
+fn find_model_number_or_search(program: &[Instruction], extreme: Extreme) -> anyhow::Result<Vec<i64>> {
+    find_model_number(program, extreme).or_else(|analysis_err| {
+        eprintln!("Searching for the model number, as it can't be worked out from the program: {}", analysis_err);
+        search_model_number(program, extreme, |progress| {
+            let digits = progress.digits.iter().map(i64::to_string).collect::<String>();
+            eprintln!("Trying model numbers starting with {} ({} states ruled out so far)", digits, progress.failed_states);
+        }).map_err(|search_err| anyhow::anyhow!("{}; searching also failed: {}", analysis_err, search_err))
+    })
+}
+
+/// The registers whose values at the start of `instructions` affect what they do, which are the
+/// ones read before they are written. `mul r 0` sets `r` to 0 whatever it was, so it counts as a
+/// write rather than a read.
+pub fn registers_read(instructions: &[Instruction]) -> Vec<Register> {
+    let (read, _) = reads_and_writes(instructions);
+    Register::ALL.iter().copied().filter(|register| read[register.index()]).collect()
+}
+
+/// The registers whose values at the start of `instructions` affect the values of `live_after` at
+/// the end: the ones read before they are written, and the ones in `live_after` that are never
+/// written, which keep their values all the way through.
+pub fn registers_live(instructions: &[Instruction], live_after: &[Register]) -> Vec<Register> {
+    let (read, written) = reads_and_writes(instructions);
+    Register::ALL.iter().copied()
+        .filter(|register| read[register.index()] || (live_after.contains(register) && !written[register.index()]))
+        .collect()
+}
+
+/// Which registers are read before they are written, and which are written at all.
+fn reads_and_writes(instructions: &[Instruction]) -> ([bool; 4], [bool; 4]) {
+    let mut written = [false; 4];
+    let mut read = [false; 4];
+    for instruction in instructions {
+        let (a, b) = match *instruction {
+            Instruction::Inp(a) | Instruction::Mul(a, Operand::Value(0)) => {
+                written[a.index()] = true;
+                continue;
+            },
+            Instruction::Add(a, b) | Instruction::Mul(a, b) | Instruction::Div(a, b) | Instruction::Mod(a, b) | Instruction::Eql(a, b) => (a, b),
+        };
+        for register in iter::once(a).chain(match b {
+            Operand::Register(b) => Some(b),
+            Operand::Value(_) => None,
+        }) {
+            read[register.index()] |= !written[register.index()];
+        }
+        written[a.index()] = true;
+    }
+    (read, written)
+}
+
+/// How far a search has got, given each time it tries a digit for one of the first
+/// `PROGRESS_DIGITS` digits.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SearchProgress<'a> {
+    /// The digits being tried, up to and including the new one.
+    pub digits: &'a [i64],
+    /// How many states have been found to lead to no valid model number.
+    pub failed_states: usize,
+}
+
+const PROGRESS_DIGITS: usize = 2;
+
+/// The parts of the program a search runs for each digit, and the registers whose values going
+/// into each part affect the rest of the program.
+struct SearchBlocks<'a> {
+    blocks: Vec<&'a [Instruction]>,
+    live_registers: Vec<Vec<Register>>,
+}
+
+/// Searches for the largest or smallest model number the program accepts, trying digits from one
+/// end in order and running the program a digit at a time. The program is split before each `inp`,
+/// and the state going into each part is remembered once nothing after it is accepted, so it
+/// isn't searched again. Only the registers that the part or a later one reads before writing go
+/// into the state, which for MONAD is only z. Calls `progress` as it goes, and fails if no model
+/// number is accepted.
+pub fn search_model_number(program: &[Instruction], extreme: Extreme, mut progress: impl FnMut(&SearchProgress)) -> anyhow::Result<Vec<i64>> {
+    let starts = program.iter().enumerate()
+        .filter(|(_, instruction)| matches!(instruction, Instruction::Inp(_)))
+        .map(|(i, _)| i)
+        .collect::<Vec<usize>>();
+    let first = *starts.first().ok_or_else(|| anyhow::anyhow!("The program doesn't read any digits"))?;
+    let alu = Alu::run(&program[..first], iter::empty())?;
+    let blocks = starts.iter().zip(starts.iter().skip(1).chain(iter::once(&program.len())))
+        .map(|(start, end)| &program[*start..*end])
+        .collect::<Vec<&[Instruction]>>();
+    // z is read at the end to check the model number.
+    let mut live = vec![Register::Z];
+    let mut live_registers = blocks.iter().rev().map(|block| {
+        live = registers_live(block, &live);
+        live.clone()
+    }).collect::<Vec<Vec<Register>>>();
+    live_registers.reverse();
+
+    let search = SearchBlocks { blocks, live_registers };
+    let mut failed = HashSet::new();
+    let mut digits = vec![];
+    if search_from(&search, alu, extreme, &mut failed, &mut digits, &mut progress) {
+        Ok(digits)
+    } else {
+        Err(anyhow::anyhow!("No model number is accepted by the program"))
+    }
+}
+
+fn search_from(search: &SearchBlocks, alu: Alu, extreme: Extreme, failed: &mut HashSet<(usize, Vec<i64>)>, digits: &mut Vec<i64>, progress: &mut impl FnMut(&SearchProgress)) -> bool {
+    let index = digits.len();
+    if index == search.blocks.len() {
+        return alu.get(Register::Z) == 0;
+    }
+    let state = (index, search.live_registers[index].iter().map(|register| alu.get(*register)).collect::<Vec<i64>>());
+    if failed.contains(&state) {
+        return false;
+    }
+
+    let order: Box<dyn Iterator<Item = i64>> = match extreme {
+        Extreme::Largest => Box::new((1..=9).rev()),
+        Extreme::Smallest => Box::new(1..=9),
+    };
+    for digit in order {
+        digits.push(digit);
+        if index < PROGRESS_DIGITS {
+            progress(&SearchProgress { digits, failed_states: failed.len() });
+        }
+        let mut next = alu;
+        let mut input = iter::once(digit);
+        // A digit that crashes the ALU can't be part of a valid model number.
+        let ran = search.blocks[index].iter().all(|instruction| next.step(instruction, &mut input).is_ok());
+        if ran && search_from(search, next, extreme, failed, digits, progress) {
+            return true;
+        }
+        digits.pop();
+    }
+    failed.insert(state);
+    false
+}
+
+/// Checks the model number with the ALU before giving it as the answer.
+fn checked_model_number(program: &[Instruction], model_number: &[i64]) -> anyhow::Result<Answer> {
+    let z = check_model_number(program, model_number)?;
+    if z != 0 {
+        return Err(anyhow::anyhow!("Model number {:?} is not valid, z: {}", model_number, z));
+    }
+    Ok(model_number.iter().fold(0, |acc, digit| acc * 10 + digit).into())
+}
+
+
+#[cfg(test)]
+mod tests {
+    use crate::day_24::*;
+
+    pub(super) const TEST_INPUT_BINARY: &str = r#"inp w
+add z w
+mod z 2
+div w 2
+add y w
+mod y 2
+div w 2
+add x w
+mod x 2
+div w 2
+mod w 2
+"#;
+
+    /// A MONAD program with the same parameters as the one the hard-coded model numbers are for.
+    pub(super) fn get_monad_program() -> Vec<Instruction> {
+        let a = [14, 15, 13, -10, 14, -3, -14, 12, 14, 12, -6, -6, -2, -9];
+        let b = [8, 11, 2, 11, 1, 5, 10, 6, 1, 11, 9, 14, 11, 2];
+        let k = [1, 1, 1, 26, 1, 26, 26, 1, 1, 1, 26, 26, 26, 26];
+        build_monad_program(&a, &b, &k)
+    }
+
+    fn build_monad_program(a: &[i64], b: &[i64], k: &[i64]) -> Vec<Instruction> {
+        let lines = (0..a.len()).flat_map(|i| vec![
+            "inp w".to_string(), "mul x 0".to_string(), "add x z".to_string(), "mod x 26".to_string(),
+            format!("div z {}", k[i]), format!("add x {}", a[i]), "eql x w".to_string(), "eql x 0".to_string(),
+            "mul y 0".to_string(), "add y 25".to_string(), "mul y x".to_string(), "add y 1".to_string(),
+            "mul z y".to_string(), "mul y 0".to_string(), "add y w".to_string(), format!("add y {}", b[i]),
+            "mul y x".to_string(), "add z y".to_string(),
+        ]).collect::<Vec<String>>();
+        parse_program(&lines).unwrap()
+    }
+
+    pub(super) fn get_test_input(s: &str) -> Vec<Instruction> {
+        parse_program(&s.split('\n').map(str::to_string).collect::<Vec<String>>()).unwrap()
+    }
+
+    #[test]
+    fn test_alu() {
+        let negate = get_test_input("inp x\nmul x -1");
+        assert_eq!(Alu::run(&negate, vec![7]).unwrap().get(Register::X), -7);
+        let three_times = get_test_input("inp z\ninp x\nmul z 3\neql z x");
+        assert_eq!(Alu::run(&three_times, vec![2, 6]).unwrap().get(Register::Z), 1);
+        assert_eq!(Alu::run(&three_times, vec![2, 5]).unwrap().get(Register::Z), 0);
+        let binary = Alu::run(&get_test_input(TEST_INPUT_BINARY), vec![11]).unwrap();
+        assert_eq!(Register::ALL.iter().map(|register| binary.get(*register)).collect::<Vec<i64>>(), [1, 0, 1, 1]);
+        assert_eq!(Alu::run(&get_test_input("inp x\ndiv x -2"), vec![7]).unwrap().get(Register::X), -3);
+    }
+
+    #[test]
+    fn test_alu_errors() {
+        let run = |s: &str| Alu::run(&get_test_input(s), vec![5]).unwrap_err().to_string();
+        assert_eq!(run("inp x\ndiv x y"), "Instruction 2 (div x y) failed: division by zero");
+        assert_eq!(run("inp x\nmul x -1\nmod x 3"), "Instruction 3 (mod x 3) failed: modulo of -5 by 3");
+        assert_eq!(run("inp x\nmod x 0"), "Instruction 2 (mod x 0) failed: modulo of 5 by 0");
+        assert_eq!(run("inp x\ninp y"), "Instruction 2 (inp y) failed: ran out of input");
+        assert_eq!(run("inp z\nmul z 1000000000\nmul z z\nmul z z"), "Instruction 3 (mul z z) failed: 5000000000 * 5000000000 does not fit in 64 bits");
+    }
+
+    #[test]
+    fn test_analysis() {
+        let blocks = extract_blocks(&get_monad_program()).unwrap();
+        assert_eq!(blocks.len(), 14);
+        assert_eq!(blocks[3], Block { a: -10, b: 11, k: 26 });
+        let constraints = find_constraints(&blocks).unwrap().iter().map(|c| (c.push, c.pop, c.offset)).collect::<Vec<_>>();
+        assert_eq!(constraints, [(2, 3, -8), (4, 5, -2), (1, 6, -3), (9, 10, 5), (8, 11, -5), (7, 12, 4), (0, 13, -1)]);
+        let unbalanced = [Block { a: 12, b: 1, k: 1 }, Block { a: -3, b: 2, k: 26 }, Block { a: -3, b: 2, k: 26 }];
+        assert_eq!(find_constraints(&unbalanced).unwrap_err().to_string(), "Block 3 pops z when nothing has been pushed");
+        let too_far = [Constraint { push: 0, pop: 1, offset: 9 }];
+        assert!(model_number_from_constraints(&too_far, 2, Extreme::Largest).is_err());
+    }
+
+    #[test]
+    fn test_extract_errors() {
+        let mut program = get_monad_program();
+        program[22] = Instruction::Div(Register::Y, Operand::Value(1));
+        assert_eq!(extract_blocks(&program).unwrap_err().to_string(), "Expected instruction 5 of block 2 to be 'div z k', found 'div y 1'");
+        program[22] = Instruction::Mul(Register::X, Operand::Value(1));
+        assert_eq!(extract_blocks(&program).unwrap_err().to_string(), "Expected instruction 5 of block 2 to be 'div z k', found 'mul x 1'");
+        assert!(extract_blocks(&program[..20]).is_err());
+    }
+
+    #[test]
+    fn test_model_numbers() {
+        let program = get_monad_program();
+        assert_eq!(Day24::part_1(&program).unwrap(), Answer::Int(99919765949498));
+        assert_eq!(Day24::part_2(&program).unwrap(), Answer::Int(24913111616151));
+        assert_ne!(check_model_number(&program, &[1; 14]).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_search() {
+        // Blocks 3 to 6 of the MONAD program, which push and pop two digits.
+        let program = build_monad_program(&[13, -10, 14, -3], &[2, 11, 1, 5], &[1, 26, 1, 26]);
+        let mut reports = vec![];
+        let largest = search_model_number(&program, Extreme::Largest, |progress| reports.push(progress.digits.to_vec())).unwrap();
+        assert_eq!(largest, [9, 1, 9, 7]);
+        assert_eq!(reports, [vec![9], vec![9, 9], vec![9, 8], vec![9, 7], vec![9, 6], vec![9, 5], vec![9, 4], vec![9, 3], vec![9, 2], vec![9, 1]]);
+        assert_eq!(search_model_number(&program, Extreme::Smallest, |_| {}).unwrap(), [9, 1, 3, 1]);
+        let constraints = find_constraints(&extract_blocks(&program).unwrap()).unwrap();
+        assert_eq!(model_number_from_constraints(&constraints, 4, Extreme::Largest).unwrap(), largest);
+
+        // Not made of MONAD's blocks, so the parts have to search. z ends up as digit 1 - digit 2 - 2.
+        let program = get_test_input("inp w\nadd z w\ninp w\nmul w -1\nadd z w\nadd z -2");
+        assert_eq!(registers_read(&program[2..]), [Register::Z]);
+        assert_eq!(Day24::part_1(&program).unwrap(), Answer::Int(97));
+        assert_eq!(Day24::part_2(&program).unwrap(), Answer::Int(31));
+        assert_eq!(search_model_number(&get_test_input("inp z\nadd z 10"), Extreme::Largest, |_| {}).unwrap_err().to_string(), "No model number is accepted by the program");
+        assert_eq!(Day24::part_1(&get_test_input("inp z\nadd z 10")).unwrap_err().to_string(),
+            "Expected MONAD to be made of blocks of 18 instructions, found 2 instructions; searching also failed: No model number is accepted by the program");
+        assert_eq!(registers_read(&get_test_input("inp w\nmul x 0\nadd x y\neql y w\nmod z x")), [Register::Y, Register::Z]);
+
+        // x is set by the first digit and only read after the last, so it has to be remembered
+        // through the blocks in between, which don't read it.
+        let program = get_test_input("inp x\ninp w\ninp w\nadd z x\nadd z -5");
+        assert_eq!(registers_live(&program[1..2], &[Register::X, Register::Z]), [Register::X, Register::Z]);
+        assert_eq!(registers_live(&program[2..], &[Register::Z]), [Register::X, Register::Z]);
+        assert_eq!(search_model_number(&program, Extreme::Largest, |_| {}).unwrap(), [5, 9, 9]);
+        assert_eq!(search_model_number(&program, Extreme::Smallest, |_| {}).unwrap(), [5, 1, 1]);
+        assert_eq!(check_model_number(&program, &[5, 9, 9]).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let parse = |s: &str| parse_instruction(0, s).unwrap_err().to_string();
+        assert_eq!(parse(""), "line 1, column 1: expected an instruction");
+        assert_eq!(parse("   "), "line 1, column 1: expected an instruction");
+        assert_eq!(parse("add x"), "line 1, column 1: expected 'add' to have 2 arguments, found 1");
+        assert_eq!(parse("inp x y"), "line 1, column 1: expected 'inp' to have 1 arguments, found 2");
+        assert_eq!(parse("sub x 1"), "line 1, column 1: unknown instruction 'sub'");
+        assert_eq!(parse("add v 1"), "line 1, column 5: expected a register from w to z, found 'v'");
+        assert_eq!(parse("add x 1o"), "line 1, column 7: could not parse '1o': invalid digit found in string");
+        assert_eq!(parse_instruction(0, "eql x w").unwrap().to_string(), "eql x w");
     }
-    println!("Number: {} z: {}", model_number.iter().map(|i| i.to_string()).collect::<String>(), z);
 }
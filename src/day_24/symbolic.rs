@@ -0,0 +1,538 @@
+use std::fmt;
+use crate::day_24::{Instruction, Operand, Register};
+
+
+/// The operations of the instructions that take two arguments.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Op {
+    Add,
+    Mul,
+    Div,
+    Mod,
+    Eql,
+}
+
+impl Op {
+    /// Applies the operation as the ALU would, or returns `None` where the ALU would crash, which
+    /// includes results that don't fit in 64 bits.
+    pub fn evaluate(self, a: i64, b: i64) -> Option<i64> {
+        match self {
+            Op::Add => a.checked_add(b),
+            Op::Mul => a.checked_mul(b),
+            Op::Div => a.checked_div(b),
+            Op::Mod if a < 0 || b <= 0 => None,
+            Op::Mod => Some(a % b),
+            Op::Eql => Some((a == b) as i64),
+        }
+    }
+
+    fn symbol(self) -> &'static str {
+        match self {
+            Op::Add => "+",
+            Op::Mul => "*",
+            Op::Div => "/",
+            Op::Mod => "%",
+            Op::Eql => "==",
+        }
+    }
+
+    /// How tightly the operation binds when printed, so that parentheses are only added where needed.
+    fn precedence(self) -> u8 {
+        match self {
+            Op::Eql => 0,
+            Op::Add => 1,
+            Op::Mul | Op::Div | Op::Mod => 2,
+        }
+    }
+}
+
+/// Splits an instruction into the register it writes to, and the operation and second argument,
+/// which `inp` doesn't have.
+fn decompose(instruction: &Instruction) -> (Register, Option<(Op, Operand)>) {
+    match *instruction {
+        Instruction::Inp(a) => (a, None),
+        Instruction::Add(a, b) => (a, Some((Op::Add, b))),
+        Instruction::Mul(a, b) => (a, Some((Op::Mul, b))),
+        Instruction::Div(a, b) => (a, Some((Op::Div, b))),
+        Instruction::Mod(a, b) => (a, Some((Op::Mod, b))),
+        Instruction::Eql(a, b) => (a, Some((Op::Eql, b))),
+    }
+}
+
+/// The values a register can have, from `min` to `max` inclusive. Bounds saturate rather than
+/// overflow, and operations assume the program doesn't crash, so a `mod` only sees the values it
+/// would accept and a `div` ignores dividing by zero.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Interval {
+    pub min: i64,
+    pub max: i64,
+}
+
+impl Interval {
+    /// What `inp` can read, as model numbers only have the digits 1 to 9.
+    pub const DIGIT: Interval = Interval { min: 1, max: 9 };
+
+    pub fn constant(value: i64) -> Self {
+        Interval { min: value, max: value }
+    }
+
+    /// The only value the interval has. A bound of `i64::MIN` or `i64::MAX` may be where an
+    /// overflowing result saturated, so those don't count as known values.
+    pub fn as_constant(&self) -> Option<i64> {
+        if self.min == self.max && !self.touches_limits() { Some(self.min) } else { None }
+    }
+
+    /// Whether a bound is at the limit of an `i64`, where it may have saturated.
+    fn touches_limits(&self) -> bool {
+        self.min == i64::MIN || self.max == i64::MAX
+    }
+
+    /// The smallest interval containing all of `values`, which must not be empty.
+    fn spanning(values: impl IntoIterator<Item = i64>) -> Self {
+        values.into_iter().fold(None, |interval: Option<Interval>, value| Some(match interval {
+            Some(interval) => Interval { min: interval.min.min(value), max: interval.max.max(value) },
+            None => Interval::constant(value),
+        })).expect("an interval spans at least one value")
+    }
+
+    /// The values the result of `op` can have. Addition and multiplication are at their extremes
+    /// at the corners, and so is division for each sign of divisor, as it is monotonic in each
+    /// argument.
+    pub fn apply(self, op: Op, other: Interval) -> Interval {
+        let corners = |f: &dyn Fn(i64, i64) -> i64, b: Interval| Interval::spanning(
+            [(self.min, b.min), (self.min, b.max), (self.max, b.min), (self.max, b.max)].iter().map(|(x, y)| f(*x, *y)));
+        match op {
+            Op::Add => Interval { min: self.min.saturating_add(other.min), max: self.max.saturating_add(other.max) },
+            Op::Mul => corners(&|x, y| x.saturating_mul(y), other),
+            Op::Div => {
+                let negative = Interval { min: other.min, max: other.max.min(-1) };
+                let positive = Interval { min: other.min.max(1), max: other.max };
+                let parts = [negative, positive].iter()
+                    .filter(|part| part.min <= part.max)
+                    .map(|part| corners(&|x, y| x.saturating_div(y), *part))
+                    .collect::<Vec<Interval>>();
+                if parts.is_empty() {
+                    // Always dividing by zero, which would crash.
+                    return self;
+                }
+                Interval::spanning(parts.iter().flat_map(|part| vec![part.min, part.max]))
+            },
+            Op::Mod => {
+                let dividend = Interval { min: self.min.max(0), max: self.max };
+                let divisor_max = other.max;
+                if dividend.max < other.min.max(1) {
+                    dividend
+                } else if dividend.min > dividend.max || divisor_max < 1 {
+                    // Always crashing.
+                    Interval::constant(0)
+                } else {
+                    Interval { min: 0, max: dividend.max.min(divisor_max - 1) }
+                }
+            },
+            Op::Eql => match (self.as_constant(), other.as_constant()) {
+                (Some(a), Some(b)) => Interval::constant((a == b) as i64),
+                _ if self.max < other.min || other.max < self.min => Interval::constant(0),
+                _ => Interval { min: 0, max: 1 },
+            },
+        }
+    }
+}
+
+impl fmt::Display for Interval {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.min == self.max {
+            write!(f, "{}", self.min)
+        } else {
+            write!(f, "{}..={}", self.min, self.max)
+        }
+    }
+}
+
+/// The range of each register after each instruction of `program`, with every register
+/// starting at 0 and every input being a digit.
+pub fn register_intervals(program: &[Instruction]) -> Vec<[Interval; 4]> {
+    let mut intervals = [Interval::constant(0); 4];
+    program.iter().map(|instruction| {
+        let (register, operation) = decompose(instruction);
+        intervals[register.index()] = match operation {
+            None => Interval::DIGIT,
+            Some((op, Operand::Register(b))) => intervals[register.index()].apply(op, intervals[b.index()]),
+            Some((op, Operand::Value(b))) => intervals[register.index()].apply(op, Interval::constant(b)),
+        };
+        intervals
+    }).collect()
+}
+
+/// The value of a register in terms of the inputs, and the values of the registers just before
+/// the statement it appears in.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Expr {
+    Const(i64),
+    /// The nth digit read, counting from zero.
+    Input(usize),
+    Register(Register),
+    Binary(Op, Box<Expr>, Box<Expr>),
+}
+
+impl Expr {
+    fn binary(op: Op, a: Expr, b: Expr) -> Expr {
+        Expr::Binary(op, Box::new(a), Box::new(b))
+    }
+
+    fn size(&self) -> usize {
+        match self {
+            Expr::Binary(_, a, b) => 1 + a.size() + b.size(),
+            _ => 1,
+        }
+    }
+
+    pub fn uses(&self, register: Register) -> bool {
+        self.use_count(register) > 0
+    }
+
+    fn use_count(&self, register: Register) -> usize {
+        match self {
+            Expr::Register(r) => (*r == register) as usize,
+            Expr::Binary(_, a, b) => a.use_count(register) + b.use_count(register),
+            _ => 0,
+        }
+    }
+
+    /// Replaces every read of `register` with `value`.
+    fn substitute(&self, register: Register, value: &Expr) -> Expr {
+        match self {
+            Expr::Register(r) if *r == register => value.clone(),
+            Expr::Binary(op, a, b) => Expr::binary(*op, a.substitute(register, value), b.substitute(register, value)),
+            expr => expr.clone(),
+        }
+    }
+
+    /// The values the expression can have, given the values the registers can have.
+    pub fn interval(&self, registers: &[Interval; 4]) -> Interval {
+        match self {
+            Expr::Const(value) => Interval::constant(*value),
+            Expr::Input(_) => Interval::DIGIT,
+            Expr::Register(register) => registers[register.index()],
+            Expr::Binary(op, a, b) => a.interval(registers).apply(*op, b.interval(registers)),
+        }
+    }
+
+    /// Works out the value, or returns `None` if the ALU would crash or there isn't enough input.
+    pub fn evaluate(&self, registers: &[i64; 4], input: &[i64]) -> Option<i64> {
+        match self {
+            Expr::Const(value) => Some(*value),
+            Expr::Input(i) => input.get(*i).copied(),
+            Expr::Register(register) => Some(registers[register.index()]),
+            Expr::Binary(op, a, b) => op.evaluate(a.evaluate(registers, input)?, b.evaluate(registers, input)?),
+        }
+    }
+
+    fn write(&self, f: &mut fmt::Formatter, min_precedence: u8) -> fmt::Result {
+        let (op, a, b) = match self {
+            Expr::Const(value) => return write!(f, "{}", value),
+            Expr::Input(i) => return write!(f, "input[{}]", i),
+            Expr::Register(register) => return write!(f, "{}", register),
+            Expr::Binary(op, a, b) => (*op, a, b),
+        };
+        let parenthesise = op.precedence() < min_precedence;
+        if parenthesise {
+            write!(f, "(")?;
+        }
+        match (op, a.as_ref(), b.as_ref()) {
+            // `eql x 0` after another `eql` inverts it.
+            (Op::Eql, Expr::Binary(Op::Eql, x, y), Expr::Const(0)) => {
+                x.write(f, 1)?;
+                write!(f, " != ")?;
+                y.write(f, 1)?;
+            },
+            (Op::Add, a, Expr::Const(value)) if *value < 0 => {
+                a.write(f, 1)?;
+                write!(f, " - {}", -value)?;
+            },
+            (op, a, b) => {
+                a.write(f, op.precedence())?;
+                write!(f, " {} ", op.symbol())?;
+                // Only addition and multiplication can leave out the parentheses on the right.
+                let associative = op == Op::Add || op == Op::Mul;
+                b.write(f, op.precedence() + if associative { 0 } else { 1 })?;
+            },
+        }
+        if parenthesise {
+            write!(f, ")")?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for Expr {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.write(f, 0)
+    }
+}
+
+/// Builds `a op b`, folding it into something simpler where the values `registers` can have
+/// allow it: constants are worked out, anything that can only have one value becomes that value
+/// (such as multiplying by 0, or comparing values that can never be equal), and operations that
+/// leave their argument as it is are dropped.
+pub fn fold(op: Op, a: Expr, b: Expr, registers: &[Interval; 4]) -> Expr {
+    if let (Expr::Const(x), Expr::Const(y)) = (&a, &b) {
+        return match op.evaluate(*x, *y) {
+            Some(value) => Expr::Const(value),
+            None => Expr::binary(op, a, b),
+        };
+    }
+    if let Some(value) = a.interval(registers).apply(op, b.interval(registers)).as_constant() {
+        return Expr::Const(value);
+    }
+    let below = |x: &Expr, m: i64| {
+        let interval = x.interval(registers);
+        interval.min >= 0 && interval.max < m
+    };
+    match (op, a, b) {
+        (Op::Add, x, Expr::Const(0)) | (Op::Add, Expr::Const(0), x) => x,
+        (Op::Mul, x, Expr::Const(1)) | (Op::Mul, Expr::Const(1), x) => x,
+        (Op::Div, x, Expr::Const(1)) => x,
+        (Op::Mod, x, Expr::Const(m)) if below(&x, m) => x,
+        // Keep constants on the right, so they can be combined.
+        (Op::Add, c @ Expr::Const(_), x) | (Op::Mul, c @ Expr::Const(_), x) | (Op::Eql, c @ Expr::Const(_), x) => fold(op, x, c, registers),
+        // Only combined if neither `x + c` nor `c + d` could overflow, as otherwise the ALU might
+        // fail where the combined sum wouldn't.
+        (Op::Add, Expr::Binary(Op::Add, x, c), Expr::Const(d)) => match *c {
+            Expr::Const(c) if c.checked_add(d).is_some() && !x.interval(registers).apply(Op::Add, Interval::constant(c)).touches_limits() => {
+                fold(Op::Add, *x, Expr::Const(c + d), registers)
+            },
+            c => Expr::binary(Op::Add, Expr::binary(Op::Add, *x, c), Expr::Const(d)),
+        },
+        (op, a, b) => Expr::binary(op, a, b),
+    }
+}
+
+/// Sets a register to the value of an expression.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Statement {
+    pub register: Register,
+    pub expr: Expr,
+    /// The values the register can have afterwards.
+    pub interval: Interval,
+}
+
+impl fmt::Display for Statement {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} = {}", self.register, self.expr)
+    }
+}
+
+/// Statements are only substituted into the statement that reads them while the result is no
+/// larger than this, so that they stay readable.
+const INLINE_LIMIT: usize = 15;
+
+/// Evaluates `program` symbolically, turning each instruction into a statement with the values
+/// of the registers it reads substituted in, folded with `fold`, so that runs of instructions
+/// such as `mul x 0` then `add x z` become `x = z`. Only constants, inputs and copies of other
+/// registers are substituted like this, while they are still current. Afterwards, statements
+/// whose results are never read, either by a later statement or as one of the `outputs` at the
+/// end, are removed, and statements read by only one other statement are merged into it.
+pub fn simplify(program: &[Instruction], outputs: &[Register]) -> Vec<Statement> {
+    let mut known = [Some(Expr::Const(0)), Some(Expr::Const(0)), Some(Expr::Const(0)), Some(Expr::Const(0))];
+    let mut intervals = [Interval::constant(0); 4];
+    let mut statements = vec![];
+    let mut inputs = 0;
+    for instruction in program {
+        let current = |register: Register| known[register.index()].clone().unwrap_or(Expr::Register(register));
+        let (register, operation) = decompose(instruction);
+        let expr = match operation {
+            None => {
+                inputs += 1;
+                Expr::Input(inputs - 1)
+            },
+            Some((op, Operand::Register(b))) => fold(op, current(register), current(b), &intervals),
+            Some((op, Operand::Value(b))) => fold(op, current(register), Expr::Const(b), &intervals),
+        };
+        let interval = expr.interval(&intervals);
+
+        for value in known.iter_mut() {
+            if value.as_ref().is_some_and(|value| value.uses(register)) {
+                *value = None;
+            }
+        }
+        known[register.index()] = if expr.size() == 1 && !expr.uses(register) { Some(expr.clone()) } else { None };
+        intervals[register.index()] = interval;
+        if expr != Expr::Register(register) {
+            statements.push(Statement { register, expr, interval });
+        }
+    }
+    inline_single_uses(eliminate_dead_stores(statements, outputs), outputs)
+}
+
+/// Removes the statements whose results are overwritten before anything reads them.
+pub fn eliminate_dead_stores(statements: Vec<Statement>, outputs: &[Register]) -> Vec<Statement> {
+    let mut live = [false; 4];
+    for output in outputs {
+        live[output.index()] = true;
+    }
+    let mut kept = vec![];
+    for statement in statements.into_iter().rev() {
+        if !live[statement.register.index()] {
+            continue;
+        }
+        live[statement.register.index()] = false;
+        for register in Register::ALL.iter() {
+            if statement.expr.uses(*register) {
+                live[register.index()] = true;
+            }
+        }
+        kept.push(statement);
+    }
+    kept.reverse();
+    kept
+}
+
+/// Merges each statement into the only statement that reads its result, as long as the registers
+/// it reads haven't changed in between and the result isn't too large.
+pub fn inline_single_uses(mut statements: Vec<Statement>, outputs: &[Register]) -> Vec<Statement> {
+    let mut i = 0;
+    while i < statements.len() {
+        match only_reader(&statements, i, outputs) {
+            Some(j) if statements[j].expr.substitute(statements[i].register, &statements[i].expr).size() <= INLINE_LIMIT => {
+                let statement = statements.remove(i);
+                let reader = &mut statements[j - 1];
+                reader.expr = reader.expr.substitute(statement.register, &statement.expr);
+            },
+            _ => i += 1,
+        }
+    }
+    statements
+}
+
+/// Finds the statement that reads the result of statement `i`, if exactly one does, reading it
+/// once, and statement `i` could be moved there without changing its result.
+fn only_reader(statements: &[Statement], i: usize, outputs: &[Register]) -> Option<usize> {
+    let statement = &statements[i];
+    let mut reader = None;
+    for (j, later) in statements.iter().enumerate().skip(i + 1) {
+        match later.expr.use_count(statement.register) {
+            0 => {},
+            1 if reader.is_none() => {
+                if statements[i + 1..j].iter().any(|between| statement.expr.uses(between.register)) {
+                    return None;
+                }
+                reader = Some(j);
+            },
+            _ => return None,
+        }
+        if later.register == statement.register {
+            return reader;
+        }
+    }
+    // The result is still there at the end, where it counts if it's an output.
+    if outputs.contains(&statement.register) { None } else { reader }
+}
+
+/// Runs the statements from every register being 0, or returns `None` if the ALU would crash.
+pub fn run_statements(statements: &[Statement], input: &[i64]) -> Option<[i64; 4]> {
+    let mut registers = [0; 4];
+    for statement in statements {
+        registers[statement.register.index()] = statement.expr.evaluate(&registers, input)?;
+    }
+    Some(registers)
+}
+
+/// Prints the statements as pseudo-code, one per line.
+pub fn pseudo_code(statements: &[Statement]) -> String {
+    statements.iter().map(Statement::to_string).collect::<Vec<String>>().join("\n")
+}
+
+
+#[cfg(test)]
+mod tests {
+    use crate::day_24::*;
+    use crate::day_24::tests::{get_monad_program, get_test_input, TEST_INPUT_BINARY};
+
+    #[test]
+    fn test_intervals() {
+        let program = get_test_input("inp w\nadd x w\nmul x -3\nadd x 30\nmod x 5\ndiv w 2\neql x 7\ninp y\nmod y w");
+        let intervals = register_intervals(&program).iter().map(|registers| registers.iter().map(Interval::to_string).collect::<Vec<String>>().join(" "))
+            .collect::<Vec<String>>();
+        assert_eq!(intervals, [
+            "1..=9 0 0 0", "1..=9 1..=9 0 0", "1..=9 -27..=-3 0 0", "1..=9 3..=27 0 0", "1..=9 0..=4 0 0",
+            "0..=4 0..=4 0 0", "0..=4 0 0 0", "0..=4 0 1..=9 0", "0..=4 0 0..=3 0",
+        ]);
+        assert_eq!(Interval { min: -7, max: 8 }.apply(Op::Div, Interval { min: -2, max: 3 }), Interval { min: -8, max: 8 });
+        assert_eq!(Interval { min: 0, max: 20 }.apply(Op::Mod, Interval::constant(26)), Interval { min: 0, max: 20 });
+    }
+
+    #[test]
+    fn test_simplify() {
+        let program = get_monad_program();
+        let first_blocks = simplify(&program[..18 * 4], &[Register::Z]);
+        // z is divided before the result of `mod x 26` is used, so that can't be merged any further.
+        assert_eq!(pseudo_code(&first_blocks), r#"z = ((input[0] + 8) * 26 + input[1] + 11) * 26 + input[2] + 2
+x = z % 26
+x = x - 10 != input[3]
+z = z / 26 * (x * 25 + 1) + (input[3] + 11) * x"#);
+        assert!(first_blocks.iter().all(|statement| statement.interval.min >= 0));
+
+        // The simplified program must give the same results as the original.
+        let simplified = simplify(&program, &[Register::Z]);
+        let mut seed = 12345u64;
+        for _ in 0..200 {
+            let digits = (0..14).map(|_| {
+                seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+                (seed >> 33) as i64 % 9 + 1
+            }).collect::<Vec<i64>>();
+            let z = run_statements(&simplified, &digits).unwrap()[Register::Z.index()];
+            assert_eq!(z, check_model_number(&program, &digits).unwrap());
+        }
+        assert_eq!(run_statements(&simplified, &[9, 9, 9, 1, 9, 7, 6, 5, 9, 4, 9, 4, 9, 8]).unwrap()[Register::Z.index()], 0);
+
+        let binary = get_test_input(TEST_INPUT_BINARY);
+        let simplified = simplify(&binary, &Register::ALL);
+        // The input is a digit, so it is already less than 2 by the time w is taken modulo 2.
+        assert_eq!(pseudo_code(&simplified), r#"z = input[0] % 2
+w = input[0] / 2
+y = w % 2
+w = w / 2
+x = w % 2
+w = w / 2"#);
+        for digit in 1..=9 {
+            let alu = Alu::run(&binary, vec![digit]).unwrap();
+            assert_eq!(run_statements(&simplified, &[digit]).unwrap(), [alu.get(Register::W), alu.get(Register::X), alu.get(Register::Y), alu.get(Register::Z)]);
+        }
+    }
+
+    #[test]
+    fn test_fold() {
+        let registers = [Interval::DIGIT, Interval { min: 0, max: 100 }, Interval::constant(0), Interval::constant(0)];
+        let (w, x) = (Expr::Register(Register::W), Expr::Register(Register::X));
+        assert_eq!(fold(Op::Mul, x.clone(), Expr::Const(0), &registers), Expr::Const(0));
+        assert_eq!(fold(Op::Eql, Expr::Const(12), w.clone(), &registers), Expr::Const(0));
+        assert_eq!(fold(Op::Mod, w.clone(), Expr::Const(26), &registers), w);
+        assert_eq!(fold(Op::Add, Expr::Const(3), fold(Op::Add, x.clone(), Expr::Const(-3), &registers), &registers), x);
+        assert_eq!(fold(Op::Div, Expr::Const(1), Expr::Const(0), &registers).to_string(), "1 / 0");
+        assert_eq!(fold(Op::Div, x.clone(), fold(Op::Div, w, Expr::Const(2), &registers), &registers).to_string(), "x / (w / 2)");
+    }
+
+    #[test]
+    fn test_overflow() {
+        // Folding leaves alone whatever would overflow in the ALU, rather than overflowing itself.
+        let program = get_test_input("add x 9223372036854775807\nadd x 1");
+        let statements = simplify(&program, &[Register::X]);
+        assert_eq!(pseudo_code(&statements), "x = 9223372036854775807 + 1");
+        assert_eq!(run_statements(&statements, &[]), None);
+        assert!(Alu::run(&program, vec![]).is_err());
+        let registers = [Interval::DIGIT, Interval::constant(0), Interval::constant(0), Interval::constant(0)];
+        assert_eq!(fold(Op::Div, Expr::Const(i64::MIN), Expr::Const(-1), &registers).to_string(), "-9223372036854775808 / -1");
+
+        // A sum that saturates at the limit isn't taken to be that one value.
+        let program = get_test_input("inp x\nadd x 9223372036854775806\nadd x 1");
+        let statements = simplify(&program, &[Register::X]);
+        assert_eq!(pseudo_code(&statements), "x = input[0] + 9223372036854775806 + 1");
+        assert_eq!(run_statements(&statements, &[0]).unwrap()[Register::X as usize], i64::MAX);
+        assert_eq!(Alu::run(&program, vec![0]).unwrap().get(Register::X), i64::MAX);
+        assert_eq!(run_statements(&statements, &[1]), None);
+        let w = Expr::Register(Register::W);
+        let near_limit = fold(Op::Add, w.clone(), Expr::Const(i64::MAX - 9), &registers);
+        assert_eq!(fold(Op::Add, near_limit, Expr::Const(-5), &registers).to_string(), "w + 9223372036854775798 - 5");
+        assert_eq!(fold(Op::Add, fold(Op::Add, w, Expr::Const(10), &registers), Expr::Const(-5), &registers).to_string(), "w + 5");
+    }
+}
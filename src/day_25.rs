@@ -0,0 +1,198 @@
+use std::collections::HashMap;
+use std::fmt;
+use crate::input::PuzzleInput;
+use crate::registry::Example;
+use crate::solution::{Answer, Solution};
+use crate::utils::{error_at, Grid};
+
+
+/// Day 25: Sea Cucumber
+/// https://adventofcode.com/2021/day/25
+pub struct Day25;
+
+impl Solution for Day25 {
+    type Input = SeaFloor;
+
+    fn parse(input: &PuzzleInput) -> anyhow::Result<Self::Input> {
+        parse_sea_floor(input.lines())
+    }
+
+    fn part_1(sea_floor: &Self::Input) -> anyhow::Result<Answer> {
+        Ok(steps_until_stopped(sea_floor)?.into())
+    }
+
+    fn part_2(_: &Self::Input) -> anyhow::Result<Answer> {
+        Err(anyhow::anyhow!("Day 25 has no second puzzle"))
+    }
+}
+
+register_day! {
+    day: 25,
+    title: "Sea Cucumber",
+    solution: Day25,
+    parts: [1],
+    examples: [
+        Example {
+            input: r#"v...>>.vv>
+.vv>>.vv..
+>>.>v>...v
+>>v>>.>.v.
+v>v.vv.v..
+>.>>..v...
+.vv..>.>v.
+v.v..>>v.v
+....v..v.>
+"#,
+            part_1: Some("58"),
+            part_2: None,
+        },
+    ],
+}
+
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Cell {
+    Empty,
+    East,
+    South,
+}
+
+impl Cell {
+    pub fn to_char(self) -> char {
+        match self {
+            Cell::Empty => '.',
+            Cell::East => '>',
+            Cell::South => 'v',
+        }
+    }
+}
+
+/// The sea floor, which wraps around on both axes.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SeaFloor {
+    pub cells: Grid<Cell>,
+}
+
+impl SeaFloor {
+    /// Moves the east-facing herd and then the south-facing herd, returning how many sea
+    /// cucumbers moved.
+    pub fn step(&mut self) -> usize {
+        self.move_herd(Cell::East) + self.move_herd(Cell::South)
+    }
+
+    /// Moves every sea cucumber in `herd` whose next space was empty before any of them moved.
+    fn move_herd(&mut self, herd: Cell) -> usize {
+        let (height, width) = (self.cells.height(), self.cells.width());
+        let next = |(i, j): (usize, usize)| match herd {
+            Cell::East => (i, (j + 1) % width),
+            _ => ((i + 1) % height, j),
+        };
+        let moving = self.cells.positions()
+            .filter(|position| self.cells[*position] == herd && self.cells[next(*position)] == Cell::Empty)
+            .collect::<Vec<(usize, usize)>>();
+        for position in moving.iter() {
+            self.cells[*position] = Cell::Empty;
+            self.cells[next(*position)] = herd;
+        }
+        moving.len()
+    }
+
+    /// Draws the sea floor as in the puzzle description.
+    pub fn render(&self) -> Grid<char> {
+        self.cells.map(|cell| cell.to_char())
+    }
+}
+
+impl fmt::Display for SeaFloor {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.render())
+    }
+}
+
+/// Counts the steps up to and including the first one on which no sea cucumbers move, failing if
+/// the sea floor gets back to an earlier state instead, as then they keep moving forever.
+pub fn steps_until_stopped(sea_floor: &SeaFloor) -> anyhow::Result<usize> {
+    let mut sea_floor = sea_floor.clone();
+    let mut seen = HashMap::new();
+    for steps in 0.. {
+        if let Some(earlier) = seen.insert(sea_floor.clone(), steps) {
+            return Err(anyhow::anyhow!("The sea cucumbers never stop, as they move in a loop of {} steps", steps - earlier));
+        }
+        if sea_floor.step() == 0 {
+            return Ok(steps + 1);
+        }
+    }
+    unreachable!("there are only so many states the sea floor can be in")
+}
+
+pub fn parse_sea_floor(lines: &[String]) -> anyhow::Result<SeaFloor> {
+    let rows = lines.iter().enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| line.trim_end().char_indices().map(|(j, c)| match c {
+            '.' => Ok(Cell::Empty),
+            '>' => Ok(Cell::East),
+            'v' => Ok(Cell::South),
+            _ => Err(error_at(i, j, format!("expected '>', 'v' or '.', found '{}'", c))),
+        }).collect::<anyhow::Result<Vec<Cell>>>().map(|row| (i, row)))
+        .collect::<anyhow::Result<Vec<(usize, Vec<Cell>)>>>()?;
+    let width = rows.first().ok_or_else(|| anyhow::anyhow!("The input is empty"))?.1.len();
+    if let Some((i, row)) = rows.iter().find(|(_, row)| row.len() != width) {
+        return Err(error_at(*i, 0, format!("expected {} spaces like the first row, found {}", width, row.len())));
+    }
+    Ok(SeaFloor { cells: Grid::from_rows(rows.into_iter().map(|(_, row)| row).collect())? })
+}
+
+
+#[cfg(test)]
+mod tests {
+    use crate::day_25::*;
+
+    fn get_test_input(s: &str) -> SeaFloor {
+        parse_sea_floor(PuzzleInput::from(s).lines()).unwrap()
+    }
+
+    #[test]
+    fn test_step() {
+        let mut row = get_test_input("...>>>>>...");
+        assert_eq!(row.step(), 1);
+        assert_eq!(row.to_string(), "...>>>>.>..");
+        assert_eq!(row.step(), 2);
+        assert_eq!(row.to_string(), "...>>>.>.>.");
+
+        // The east-facing herd moves first, so the south-facing one sees where it went.
+        let mut sea_floor = get_test_input("..........\n.>v....v..\n.......>..\n..........");
+        sea_floor.step();
+        assert_eq!(sea_floor.to_string(), "..........\n.>........\n..v....v>.\n..........");
+
+        // Sea cucumbers wrap around to the other side.
+        let mut sea_floor = get_test_input("...>...\n.......\n......>\nv.....>\n......>\n.......\n..vvv..");
+        sea_floor.step();
+        assert_eq!(sea_floor.to_string(), "..vv>..\n.......\n>......\nv.....>\n>......\n.......\n....v..");
+    }
+
+    #[test]
+    fn test_steps_until_stopped() {
+        let mut sea_floor = get_test_input(DAY.examples[0].input);
+        sea_floor.step();
+        assert_eq!(sea_floor.to_string(), r#"....>.>v.>
+v.v>.>v.v.
+>v>>..>v..
+>>v>v>.>.v
+.>v.v...v.
+v>>.>vvv..
+..v...>>..
+vv...>>vv.
+>.v.v..v.v"#);
+        assert_eq!(steps_until_stopped(&get_test_input(DAY.examples[0].input)).unwrap(), 58);
+        assert_eq!(steps_until_stopped(&get_test_input(">v\nv>")).unwrap(), 1);
+        assert_eq!(steps_until_stopped(&get_test_input(">..")).unwrap_err().to_string(), "The sea cucumbers never stop, as they move in a loop of 3 steps");
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let parse = |s: &str| parse_sea_floor(PuzzleInput::from(s).lines()).unwrap_err().to_string();
+        assert_eq!(parse(""), "The input is empty");
+        assert_eq!(parse("..>\n.<."), "line 2, column 2: expected '>', 'v' or '.', found '<'");
+        assert_eq!(parse("..>\n.v"), "line 2, column 1: expected 3 spaces like the first row, found 2");
+    }
+}
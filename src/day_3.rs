@@ -1,74 +1,157 @@
-fn get_gamma_rate(numbers: &Vec<String>) -> Vec<char> {
-    if numbers.len() == 0 {
-        return vec![];
-    }
+use crate::input::PuzzleInput;
+use crate::registry::Example;
+use crate::solution::{Answer, Solution};
+use crate::utils::{column_of, error_at};
+
+
+/// The most common bit in each position, with ties going to 1. Fails if a number is shorter than
+/// the first one or has a bit that isn't a 0 or 1.
+fn get_gamma_rate(numbers: &[String]) -> anyhow::Result<Vec<char>> {
+    let width = match numbers.first() {
+        Some(first) => first.len(),
+        None => return Ok(vec![]),
+    };
 
     let mut bits = vec![];
-    for j in 0..numbers[0].len() {
+    for j in 0..width {
         let mut zero_count = 0;
         let mut one_count = 0;
         for number in numbers {
-            if number.len() == 0 {
+            if number.is_empty() {
                 continue;
             }
-            if j > number.len() {
-                panic!("Number {} is larger than the first number in the sequence", number);
-            }
-            let bit = number.as_bytes()[j];
-            if bit == b'0' {
-                zero_count += 1
-            } else if bit == b'1' {
-                one_count += 1
-            } else {
-                panic!("Number {} contains the character {} which is not a 0 or 1", number, bit);
+            match number.as_bytes().get(j) {
+                Some(b'0') => zero_count += 1,
+                Some(b'1') => one_count += 1,
+                Some(bit) => return Err(anyhow::anyhow!("Number {} contains the character '{}' which is not a 0 or 1", number, *bit as char)),
+                None => return Err(anyhow::anyhow!("Number {} is shorter than the first number in the sequence", number)),
             }
         }
         bits.push(if one_count >= zero_count { '1' } else { '0' });
     }
-    return bits;
+    Ok(bits)
 }
 
-pub fn get_gamma_and_epsilon_rates(numbers: &Vec<String>) -> (Vec<char>, Vec<char>) {
-    let gamma_rate = get_gamma_rate(numbers);
-    let mut epsilon_rate = gamma_rate.clone();
-    for i in 0..epsilon_rate.len() {
-        epsilon_rate[i] = if epsilon_rate[i] == '0' { '1' } else { '0' };
+/// Parses the diagnostic report, checking that every number is made of the same number of bits.
+pub fn parse_report(lines: &[String]) -> anyhow::Result<Vec<String>> {
+    let mut numbers: Vec<String> = vec![];
+    for (i, line) in lines.iter().enumerate() {
+        let number = line.trim();
+        if number.is_empty() {
+            continue;
+        }
+        if let Some((j, c)) = number.char_indices().find(|(_, c)| *c != '0' && *c != '1') {
+            return Err(error_at(i, column_of(line, number) + j, format!("expected a 0 or 1, found '{}'", c)));
+        }
+        if let Some(first) = numbers.first() {
+            if number.len() != first.len() {
+                return Err(error_at(i, column_of(line, number), format!("expected {} bits like the first number, found {}", first.len(), number.len())));
+            }
+        }
+        numbers.push(number.to_string());
+    }
+    if numbers.is_empty() {
+        return Err(anyhow::anyhow!("The diagnostic report is empty"));
     }
-    return (gamma_rate, epsilon_rate);
+    Ok(numbers)
+}
+
+pub fn get_gamma_and_epsilon_rates(numbers: &[String]) -> anyhow::Result<(Vec<char>, Vec<char>)> {
+    let gamma_rate = get_gamma_rate(numbers)?;
+    let epsilon_rate = gamma_rate.iter()
+        .map(|bit| if *bit == '0' { '1' } else { '0' })
+        .collect::<Vec<char>>();
+    Ok((gamma_rate, epsilon_rate))
 }
 
-pub fn get_o2gen_and_co2scrubber_rates(numbers: &Vec<String>) -> (String, String) {
-    if numbers.len() == 0 {
-        panic!("Doesn't work for zero length vectors!");
+/// Finds the oxygen generator and CO2 scrubber ratings, failing if the bit criteria filter out
+/// every number before one is left.
+pub fn get_o2gen_and_co2scrubber_rates(numbers: &[String]) -> anyhow::Result<(String, String)> {
+    if numbers.is_empty() {
+        return Err(anyhow::anyhow!("The diagnostic report is empty"));
     }
 
-    let mut o2gen_numbers = numbers.clone();
+    let mut o2gen_numbers = numbers.to_vec();
     if o2gen_numbers.len() > 1 {
         for i in 0..o2gen_numbers[0].len() {
             if o2gen_numbers.len() <= 1 {
                 break;
             }
-            let gamma_rate = get_gamma_rate(&o2gen_numbers);
+            let gamma_rate = get_gamma_rate(&o2gen_numbers)?;
             o2gen_numbers = o2gen_numbers.into_iter()
-                .filter(|o2gen_number| o2gen_number.len() > 0 && o2gen_number.as_bytes()[i] as char == gamma_rate[i])
+                .filter(|o2gen_number| o2gen_number.as_bytes().get(i).map(|bit| *bit as char) == gamma_rate.get(i).copied())
                 .collect::<Vec<String>>();
         }
     }
 
-    let mut co2scrubber_numbers = numbers.clone();
+    let mut co2scrubber_numbers = numbers.to_vec();
     if co2scrubber_numbers.len() > 1 {
         for i in 0..co2scrubber_numbers[0].len() {
             if co2scrubber_numbers.len() <= 1 {
                 break;
             }
-            let (_, epsilon_rate) = get_gamma_and_epsilon_rates(&co2scrubber_numbers);
+            let (_, epsilon_rate) = get_gamma_and_epsilon_rates(&co2scrubber_numbers)?;
             co2scrubber_numbers = co2scrubber_numbers.into_iter()
-                .filter(|co2scrubber_number| co2scrubber_number.len() > 0 && co2scrubber_number.as_bytes()[i] as char == epsilon_rate[i])
+                .filter(|co2scrubber_number| co2scrubber_number.as_bytes().get(i).map(|bit| *bit as char) == epsilon_rate.get(i).copied())
                 .collect::<Vec<String>>();
         }
     }
 
-    return (o2gen_numbers[0].clone(), co2scrubber_numbers[0].clone());
+    let last_number = |numbers: Vec<String>, rating: &str| numbers.into_iter().next()
+        .ok_or_else(|| anyhow::anyhow!("Every number was filtered out while finding the {} rating", rating));
+    Ok((last_number(o2gen_numbers, "oxygen generator")?, last_number(co2scrubber_numbers, "CO2 scrubber")?))
+}
+
+/// Day 3: Binary Diagnostic
+/// https://adventofcode.com/2021/day/3
+pub struct Day3;
+
+impl Solution for Day3 {
+    type Input = Vec<String>;
+
+    fn parse(input: &PuzzleInput) -> anyhow::Result<Self::Input> {
+        parse_report(input.lines())
+    }
+
+    fn part_1(numbers: &Self::Input) -> anyhow::Result<Answer> {
+        let (gamma_rate_vec, epsilon_rate_vec) = get_gamma_and_epsilon_rates(numbers)?;
+        let gamma_rate = i64::from_str_radix(&gamma_rate_vec.iter().collect::<String>(), 2)?;
+        let epsilon_rate = i64::from_str_radix(&epsilon_rate_vec.iter().collect::<String>(), 2)?;
+        Ok((gamma_rate * epsilon_rate).into())
+    }
+
+    fn part_2(numbers: &Self::Input) -> anyhow::Result<Answer> {
+        let (o2gen_rate, co2scrubber_rate) = get_o2gen_and_co2scrubber_rates(numbers)?;
+        let o2gen_rate_i64 = i64::from_str_radix(&o2gen_rate, 2)?;
+        let co2scrubber_rate_i64 = i64::from_str_radix(&co2scrubber_rate, 2)?;
+        Ok((o2gen_rate_i64 * co2scrubber_rate_i64).into())
+    }
+}
+
+register_day! {
+    day: 3,
+    title: "Binary Diagnostic",
+    solution: Day3,
+    parts: [1, 2],
+    examples: [
+        Example {
+            input: r#"00100
+11110
+10110
+10111
+10101
+01111
+00111
+11100
+10000
+11001
+00010
+01010
+"#,
+            part_1: Some("198"),
+            part_2: Some("230"),
+        },
+    ],
 }
 
 
@@ -81,16 +164,32 @@ mod tests {
     #[test]
     fn test_get_gamma_and_epsilon_rates() {
         let numbers = TEST_INPUT.split_whitespace().map(str::to_string).collect::<Vec<String>>();
-        let (gamma_rate, epsilon_rate) = get_gamma_and_epsilon_rates(&numbers);
+        let (gamma_rate, epsilon_rate) = get_gamma_and_epsilon_rates(&numbers).unwrap();
         assert_eq!(gamma_rate.iter().collect::<String>(), "10110");
         assert_eq!(epsilon_rate.iter().collect::<String>(), "01001");
+        let rates = |numbers: &[&str]| get_gamma_and_epsilon_rates(&numbers.iter().map(|number| number.to_string()).collect::<Vec<String>>());
+        assert_eq!(rates(&["101", "1x1"]).unwrap_err().to_string(), "Number 1x1 contains the character 'x' which is not a 0 or 1");
+        assert_eq!(rates(&["101", "1"]).unwrap_err().to_string(), "Number 1 is shorter than the first number in the sequence");
     }
 
     #[test]
     fn test_get_o2gen_and_co2scrubber_rates() {
         let numbers = TEST_INPUT.split_whitespace().map(str::to_string).collect::<Vec<String>>();
-        let (o2gen_rate, co2scrubber_rate) = get_o2gen_and_co2scrubber_rates(&numbers);
+        let (o2gen_rate, co2scrubber_rate) = get_o2gen_and_co2scrubber_rates(&numbers).unwrap();
         assert_eq!(o2gen_rate, "10111");
         assert_eq!(co2scrubber_rate, "01010");
+        // Both numbers start with 1, so the CO2 scrubber criteria keep neither of them.
+        let numbers = parse_report(&["10".to_string(), "11".to_string()]).unwrap();
+        assert_eq!(Day3::part_2(&numbers).unwrap_err().to_string(), "Every number was filtered out while finding the CO2 scrubber rating");
+        assert_eq!(get_o2gen_and_co2scrubber_rates(&[]).unwrap_err().to_string(), "The diagnostic report is empty");
+    }
+
+    #[test]
+    fn test_parse_report() {
+        let parse = |s: &str| parse_report(&s.split('\n').map(str::to_string).collect::<Vec<String>>());
+        assert_eq!(parse("00100\n11110\n").unwrap(), ["00100", "11110"]);
+        assert_eq!(parse("00100\n11210").unwrap_err().to_string(), "line 2, column 3: expected a 0 or 1, found '2'");
+        assert_eq!(parse("00100\n1111").unwrap_err().to_string(), "line 2, column 1: expected 5 bits like the first number, found 4");
+        assert!(parse("").is_err());
     }
 }
@@ -1,4 +1,8 @@
 use ndarray::{Array2, ArrayView};
+use crate::input::{PuzzleInput, Section};
+use crate::registry::Example;
+use crate::solution::{Answer, Solution};
+use crate::utils::{error_at, parse_at};
 
 
 #[derive(Debug)]
@@ -34,51 +38,61 @@ impl std::clone::Clone for BingoSquare {
 impl BingoSquare {
     fn new(number: i64, marked: bool) -> Self {
         Self {
-            number: number,
-            marked: marked,
+            number,
+            marked,
         }
     }
 }
 
 impl PartialEq<BingoSquare> for BingoSquare {
     fn eq(&self, other: &BingoSquare) -> bool {
-        return self.number == other.number;
+        self.number == other.number
     }
 }
 
 
-fn parse_bingo_numbers(line: &String) -> Vec<i64> {
-    line.split(',').filter_map(|number| number.parse::<i64>().ok()).collect::<Vec<i64>>()
+/// The numbers to be drawn, in order, along with the boards being played.
+pub struct Bingo {
+    numbers: Vec<i64>,
+    boards: Vec<Array2<BingoSquare>>,
 }
 
-fn parse_bingo_board(lines: &Vec<String>) -> Array2<BingoSquare> {
+fn parse_bingo_numbers(line_index: usize, line: &str) -> anyhow::Result<Vec<i64>> {
+    line.trim().split(',').map(|number| parse_at(line_index, line, number)).collect()
+}
+
+fn parse_bingo_board(section: &Section) -> anyhow::Result<Array2<BingoSquare>> {
     let mut matrix = Array2::<BingoSquare>::zeros((0, 5));
-    for line in lines {
+    for (i, line) in (section.first_line..).zip(section.lines) {
         let row = line.split_whitespace()
-                      .map(|number| BingoSquare { number: number.parse::<i64>().unwrap(), marked: false })
-                      .collect::<Vec<BingoSquare>>();
-        if let Err(e) = matrix.push_row(ArrayView::from(&row)) {
-            panic!("Could not push row {}, onto matrix, error {}", line, e);
+                      .map(|number| Ok(BingoSquare { number: parse_at(i, line, number)?, marked: false }))
+                      .collect::<anyhow::Result<Vec<BingoSquare>>>()?;
+        if row.len() != 5 {
+            return Err(error_at(i, 0, format!("expected a row of 5 numbers, found {}", row.len())));
         }
+        matrix.push_row(ArrayView::from(&row)).map_err(|e| error_at(i, 0, e))?;
     }
-    return matrix;
+    Ok(matrix)
 }
 
-fn parse_bingo_boards(lines: &Vec<String>) -> Vec<Array2<BingoSquare>> {
-    let mut matrices = vec![];
-    let mut matrix_lines = vec![];
-    for line in lines {
-        let trimmed_line = line.trim();
-        if trimmed_line.is_empty() {
-            if !matrix_lines.is_empty() {
-                matrices.push(parse_bingo_board(&matrix_lines));
-            }
-            matrix_lines = vec![];
-            continue;
+/// Parses the drawn numbers from the first section, and a board from each section after it.
+pub fn parse_bingo(input: &PuzzleInput) -> anyhow::Result<Bingo> {
+    let sections = input.sections();
+    let (numbers_section, board_sections) = sections.split_first().ok_or_else(|| anyhow::anyhow!("The input is empty"))?;
+    if numbers_section.lines.len() != 1 {
+        return Err(error_at(numbers_section.first_line + 1, 0, "expected a blank line after the drawn numbers"));
+    }
+    let numbers = parse_bingo_numbers(numbers_section.first_line, &numbers_section.lines[0])?;
+    let boards = board_sections.iter().map(parse_bingo_board).collect::<anyhow::Result<Vec<Array2<BingoSquare>>>>()?;
+    Ok(Bingo { numbers, boards })
+}
+
+fn mark_number(board: &mut Array2<BingoSquare>, number: i64) {
+    for square in board.iter_mut() {
+        if square.number == number {
+            square.marked = true;
         }
-        matrix_lines.push(trimmed_line.to_string());
     }
-    return matrices;
 }
 
 fn is_winning(board: &Array2<BingoSquare>) -> bool {
@@ -95,78 +109,106 @@ fn is_winning(board: &Array2<BingoSquare>) -> bool {
     false
 }
 
-pub fn find_winning_bingo_board(lines: &mut Vec<String>) -> Option<(i64, Array2<BingoSquare>)> {
-    if lines.is_empty() {
-        return None;
-    }
-
-    let first_line = lines.remove(0);
-    let bingo_numbers = parse_bingo_numbers(&first_line);
-    let mut bingo_boards = parse_bingo_boards(lines);
+pub fn find_winning_bingo_board(bingo: &Bingo) -> Option<(i64, Array2<BingoSquare>)> {
+    let mut bingo_boards = bingo.boards.clone();
 
-    let mut winning_number_and_board = None;
-    for bingo_number in bingo_numbers {
-        for b_index in 0..bingo_boards.len() {
-            for i in 0..5 {
-                for j in 0..5 {
-                    if bingo_boards[b_index][[i, j]].number == bingo_number {
-                        bingo_boards[b_index][[i, j]].marked = true;
-                    }
-                }
+    for bingo_number in bingo.numbers.iter() {
+        for board in bingo_boards.iter_mut() {
+            mark_number(board, *bingo_number);
+            if is_winning(board) {
+                return Some((*bingo_number, board.clone()));
             }
-            if is_winning(&bingo_boards[b_index]) {
-                winning_number_and_board = Some((bingo_number, b_index));
-                break;
-            }
-        }
-        if winning_number_and_board.is_some() {
-            break;
         }
     }
-    winning_number_and_board.map(|(winning_number, b_index)| (winning_number, bingo_boards[b_index].clone()))
+    None
 }
 
-pub fn find_last_to_win_bingo_board(lines: &mut Vec<String>) -> Option<(i64, Array2<BingoSquare>)> {
-    if lines.is_empty() {
-        return None;
-    }
-
-    let first_line = lines.remove(0);
-    let bingo_numbers = parse_bingo_numbers(&first_line);
-    let mut bingo_boards = parse_bingo_boards(lines);
+pub fn find_last_to_win_bingo_board(bingo: &Bingo) -> Option<(i64, Array2<BingoSquare>)> {
+    let mut bingo_boards = bingo.boards.clone();
 
     let mut last_number = 0;
-    for bingo_number in bingo_numbers {
-        let mut winning_board_indices = vec![];
-        for b_index in 0..bingo_boards.len() {
-            for i in 0..5 {
-                for j in 0..5 {
-                    if bingo_boards[b_index][[i, j]].number == bingo_number {
-                        bingo_boards[b_index][[i, j]].marked = true;
-                    }
-                }
-            }
-            if is_winning(&bingo_boards[b_index]) {
-                winning_board_indices.push(b_index);
+    for bingo_number in bingo.numbers.iter() {
+        let mut winning_board_count = 0;
+        for board in bingo_boards.iter_mut() {
+            mark_number(board, *bingo_number);
+            if is_winning(board) {
+                winning_board_count += 1;
             }
         }
 
-        if winning_board_indices.len() == bingo_boards.len() {
-            last_number = bingo_number;
+        if winning_board_count == bingo_boards.len() {
+            last_number = *bingo_number;
             break;
         }
-        bingo_boards.retain(|board| !is_winning(&board));
+        bingo_boards.retain(|board| !is_winning(board));
     }
 
-    Some((last_number, bingo_boards[0].clone()))
+    Some((last_number, bingo_boards.first()?.clone()))
 }
 
 pub fn get_score(number: i64, board: &Array2<BingoSquare>) -> i64 {
-    let unmarked_numbers = board.iter()
+    let unmarked_sum: i64 = board.iter()
         .filter(|square| !square.marked)
         .map(|square| square.number)
-        .collect::<Vec<i64>>();
-    number * unmarked_numbers.iter().fold(0, |x, y| x + y)
+        .sum();
+    number * unmarked_sum
+}
+
+/// Day 4: Giant Squid
+/// https://adventofcode.com/2021/day/4
+pub struct Day4;
+
+impl Solution for Day4 {
+    type Input = Bingo;
+
+    fn parse(input: &PuzzleInput) -> anyhow::Result<Self::Input> {
+        parse_bingo(input)
+    }
+
+    fn part_1(bingo: &Self::Input) -> anyhow::Result<Answer> {
+        let (winning_number, winning_board) = find_winning_bingo_board(bingo)
+            .ok_or_else(|| anyhow::anyhow!("No board ever wins"))?;
+        Ok(get_score(winning_number, &winning_board).into())
+    }
+
+    fn part_2(bingo: &Self::Input) -> anyhow::Result<Answer> {
+        let (number, board) = find_last_to_win_bingo_board(bingo)
+            .ok_or_else(|| anyhow::anyhow!("No board is left to win last"))?;
+        Ok(get_score(number, &board).into())
+    }
+}
+
+register_day! {
+    day: 4,
+    title: "Giant Squid",
+    solution: Day4,
+    parts: [1, 2],
+    examples: [
+        Example {
+            input: r#"7,4,9,5,11,17,23,2,0,14,21,24,10,16,13,6,15,25,12,22,18,20,8,19,3,26,1
+
+22 13 17 11  0
+ 8  2 23  4 24
+21  9 14 16  7
+ 6 10  3 18  5
+ 1 12 20 15 19
+
+ 3 15  0  2 22
+ 9 18 13 17  5
+19  8  7 25 23
+20 11 10 24  4
+14 21 16 12  6
+
+14 21 17 24  4
+10 16 15  9 19
+18  8 23 26 20
+22 11 13  6  5
+ 2  0 12  3  7
+"#,
+            part_1: Some("4512"),
+            part_2: Some("1924"),
+        },
+    ],
 }
 
 
@@ -197,7 +239,7 @@ mod tests {
 
     #[test]
     fn test_find_winning_bingo_board() {
-        let winner = find_winning_bingo_board(&mut TEST_INPUT.split("\n").map(str::to_string).collect::<Vec<String>>());
+        let winner = find_winning_bingo_board(&parse_bingo(&PuzzleInput::from(TEST_INPUT)).unwrap());
         let expected_board = ndarray::array![[14, 21, 17, 24, 4], [10, 16, 15, 9, 19], [18, 8, 23, 26, 20], [22, 11, 13, 6, 5], [2, 0, 12, 3, 7]].map(|number| BingoSquare::new(*number, false));
         assert!(winner.is_some());
         if let Some((winning_number, winning_board)) = winner {
@@ -209,7 +251,7 @@ mod tests {
 
     #[test]
     fn test_find_last_winning_bingo_board() {
-        let last = find_last_to_win_bingo_board(&mut TEST_INPUT.split("\n").map(str::to_string).collect::<Vec<String>>());
+        let last = find_last_to_win_bingo_board(&parse_bingo(&PuzzleInput::from(TEST_INPUT)).unwrap());
         let expected_board = ndarray::array![[3, 15, 0, 2, 22], [9, 18, 13, 17, 5], [19, 8, 7, 25, 23], [20, 11, 10, 24, 4], [14, 21, 16, 12, 6]].map(|number| BingoSquare::new(*number, false));
         assert!(last.is_some());
         if let Some((number, board)) = last {
@@ -218,4 +260,13 @@ mod tests {
             assert_eq!(number, 13);
         }
     }
+
+    #[test]
+    fn test_parse_errors() {
+        let parse = |s: &str| parse_bingo(&PuzzleInput::from(s)).err().unwrap().to_string();
+        assert_eq!(parse("7,4,x"), "line 1, column 5: could not parse 'x': invalid digit found in string");
+        assert_eq!(parse("7,4\n\n 1  2  3  4  5\n 6  7  8 a9 10"), "line 4, column 10: could not parse 'a9': invalid digit found in string");
+        assert_eq!(parse("7,4\n\n 1  2  3  4"), "line 3, column 1: expected a row of 5 numbers, found 4");
+        assert_eq!(parse("7,4\n 1  2  3  4  5"), "line 2, column 1: expected a blank line after the drawn numbers");
+    }
 }
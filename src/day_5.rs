@@ -1,5 +1,9 @@
 use std::collections::HashMap;
 use std::convert::TryInto;
+use crate::input::PuzzleInput;
+use crate::registry::Example;
+use crate::solution::{Answer, Solution};
+use crate::utils::{column_of, error_at, parse_at};
 
 
 #[derive(PartialEq, Eq, Hash, Debug)]
@@ -10,37 +14,34 @@ struct Point {
 
 impl Point {
     fn new(x: i64, y: i64) -> Self {
-        Point { x: x, y: y }
+        Point { x, y }
     }
 
-    fn from_parts(parts: &Vec<i64>) -> Self {
-        Point { x: parts[0], y: parts[1] }
+}
+
+/// Parses a point such as `0,9`, which is found in `line` at line `line_index` of the input.
+fn parse_point(line_index: usize, line: &str, point: &str) -> anyhow::Result<Point> {
+    let coords = point.split(',').map(|coord| parse_at::<i64>(line_index, line, coord.trim())).collect::<anyhow::Result<Vec<i64>>>()?;
+    if coords.len() != 2 {
+        return Err(error_at(line_index, column_of(line, point), format!("'{}' needs to be two numbers separated by a comma", point)));
     }
+    Ok(Point::new(coords[0], coords[1]))
 }
 
 #[derive(Debug)]
-struct VentLine {
+pub struct VentLine {
     p1: Point,
     p2: Point,
 }
 
 impl VentLine {
-    fn from_string(s: &String) -> anyhow::Result<Self> {
-        let parts = s.split(" -> ")
-            .map(|part| part.split(',').filter_map(|coord| coord.parse::<i64>().ok()).collect::<Vec<i64>>())
-            .collect::<Vec<Vec<i64>>>();
+    /// Parses a vent line such as `0,9 -> 5,9` from line `line_index` of the input.
+    fn parse(line_index: usize, line: &str) -> anyhow::Result<Self> {
+        let parts = line.trim().split(" -> ").collect::<Vec<&str>>();
         if parts.len() != 2 {
-            return Err(anyhow::anyhow!("{} needs to be two parts separated by a ' -> '", s));
-        }
-        let part1 = &parts[0];
-        let part2 = &parts[1];
-        if part1.len() != 2 {
-            return Err(anyhow::anyhow!("First part of a vent line {:?} needs to be two numbers separated by a comma", part1));
+            return Err(error_at(line_index, 0, format!("'{}' needs to be two points separated by a ' -> '", line.trim())));
         }
-        if part2.len() != 2 {
-            return Err(anyhow::anyhow!("Second part of a vent line {:?} needs to be two numbers separated by a comma", part2));
-        }
-        Ok(VentLine { p1: Point::from_parts(part1), p2: Point::from_parts(part2) })
+        Ok(VentLine { p1: parse_point(line_index, line, parts[0])?, p2: parse_point(line_index, line, parts[1])? })
     }
 
     fn is_orthogonal(&self) -> bool {
@@ -82,23 +83,17 @@ impl VentLine {
     }
 }
 
-fn parse_ventlines(input: &Vec<String>) -> Vec<VentLine> {
-    input.into_iter()
-        .filter(|input_line| !input_line.trim().is_empty())
-        .map(|input_line| VentLine::from_string(&input_line.trim().to_string()).unwrap())
-        .collect::<Vec<VentLine>>()
-}
-
-fn parse_orthogonal_ventlines(input: &Vec<String>) -> Vec<VentLine> {
-    parse_ventlines(input).into_iter()
-        .filter(|line| line.is_orthogonal())
-        .collect::<Vec<VentLine>>()
+/// Parses one vent line per non-empty line of the input.
+pub fn parse_ventlines(input: &[String]) -> anyhow::Result<Vec<VentLine>> {
+    input.iter().enumerate()
+        .filter(|(_, input_line)| !input_line.trim().is_empty())
+        .map(|(i, input_line)| VentLine::parse(i, input_line))
+        .collect::<anyhow::Result<Vec<VentLine>>>()
 }
 
-pub fn count_overlapping_orthogonal_ventlines(input: &Vec<String>) -> i64 {
-    let ventlines = parse_orthogonal_ventlines(input);
+pub fn count_overlapping_orthogonal_ventlines(ventlines: &[VentLine]) -> i64 {
     let mut map: HashMap<Point, i64> = HashMap::new();
-    for ventline in ventlines {
+    for ventline in ventlines.iter().filter(|line| line.is_orthogonal()) {
         for point in ventline.points_in_orthogonal_line() {
             let entry = map.entry(point).or_insert(0);
             *entry += 1;
@@ -107,8 +102,7 @@ pub fn count_overlapping_orthogonal_ventlines(input: &Vec<String>) -> i64 {
     map.values().filter(|count| **count >= 2).count().try_into().unwrap()
 }
 
-pub fn count_overlapping_ventlines(input: &Vec<String>) -> i64 {
-    let ventlines = parse_ventlines(input);
+pub fn count_overlapping_ventlines(ventlines: &[VentLine]) -> i64 {
     let mut map: HashMap<Point, i64> = HashMap::new();
     for ventline in ventlines {
         for point in ventline.points_in_line() {
@@ -119,6 +113,49 @@ pub fn count_overlapping_ventlines(input: &Vec<String>) -> i64 {
     map.values().filter(|count| **count >= 2).count().try_into().unwrap()
 }
 
+/// Day 5: Hydrothermal Venture
+/// https://adventofcode.com/2021/day/5
+pub struct Day5;
+
+impl Solution for Day5 {
+    type Input = Vec<VentLine>;
+
+    fn parse(input: &PuzzleInput) -> anyhow::Result<Self::Input> {
+        parse_ventlines(input.lines())
+    }
+
+    fn part_1(ventlines: &Self::Input) -> anyhow::Result<Answer> {
+        Ok(count_overlapping_orthogonal_ventlines(ventlines).into())
+    }
+
+    fn part_2(ventlines: &Self::Input) -> anyhow::Result<Answer> {
+        Ok(count_overlapping_ventlines(ventlines).into())
+    }
+}
+
+register_day! {
+    day: 5,
+    title: "Hydrothermal Venture",
+    solution: Day5,
+    parts: [1, 2],
+    examples: [
+        Example {
+            input: r#"0,9 -> 5,9
+8,0 -> 0,8
+9,4 -> 3,4
+2,2 -> 2,1
+7,0 -> 7,4
+6,4 -> 2,0
+0,9 -> 2,9
+3,4 -> 1,4
+0,0 -> 8,8
+5,5 -> 8,2
+"#,
+            part_1: Some("5"),
+            part_2: Some("12"),
+        },
+    ],
+}
 
 #[cfg(test)]
 mod tests {
@@ -140,12 +177,20 @@ mod tests {
     #[test]
     fn test_count_orthogonal() {
         let test_input = TEST_INPUT.trim().split('\n').map(|line| line.trim().to_string()).collect::<Vec<String>>();
-        assert_eq!(count_overlapping_orthogonal_ventlines(&test_input), 5);
+        assert_eq!(count_overlapping_orthogonal_ventlines(&parse_ventlines(&test_input).unwrap()), 5);
     }
 
     #[test]
     fn test_count_all() {
         let test_input = TEST_INPUT.trim().split('\n').map(|line| line.trim().to_string()).collect::<Vec<String>>();
-        assert_eq!(count_overlapping_ventlines(&test_input), 12);
+        assert_eq!(count_overlapping_ventlines(&parse_ventlines(&test_input).unwrap()), 12);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let parse = |s: &str| parse_ventlines(&s.split('\n').map(str::to_string).collect::<Vec<String>>()).unwrap_err().to_string();
+        assert_eq!(parse("0,9 -> 5,9\n8,0 > 0,8"), "line 2, column 1: '8,0 > 0,8' needs to be two points separated by a ' -> '");
+        assert_eq!(parse("0,9 -> 5,9\n8,0 -> 0,y"), "line 2, column 10: could not parse 'y': invalid digit found in string");
+        assert_eq!(parse("8,0 -> 0,8,1"), "line 1, column 8: '0,8,1' needs to be two numbers separated by a comma");
     }
 }
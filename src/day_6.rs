@@ -1,37 +1,74 @@
+use crate::input::PuzzleInput;
+use crate::registry::Example;
+use crate::solution::{Answer, Solution};
+use crate::utils::{column_of, error_at, parse_at};
+
+
 const NEW_FISH_TIMER: usize = 8;
 const RESET_FISH_TIMER: usize = 6;
 
-fn parse_initial_state(input: &Vec<String>) -> Vec<i64> {
+/// Parses the comma separated fish timers into a count of how many fish have each timer value.
+pub fn parse_initial_state(input: &[String]) -> anyhow::Result<Vec<i64>> {
     let mut counts = vec![0; NEW_FISH_TIMER + 1];
-    for timer in input[0].split(',') {
-        let timer_parsed = timer.parse::<i64>();
-        if let Ok(timer_as_number) = timer_parsed {
-            let timer_as_usize = timer_as_number as usize;
-            counts[timer_as_usize] += 1;
-        }
-    }
-    counts
+    let first_line = input.first().map(String::as_str).unwrap_or("");
+    if first_line.trim().is_empty() {
+        return Err(anyhow::anyhow!("The input is empty"));
+    }
+    for timer in first_line.trim().split(',') {
+        let timer_as_usize = parse_at::<usize>(0, first_line, timer)?;
+        let count = counts.get_mut(timer_as_usize)
+            .ok_or_else(|| error_at(0, column_of(first_line, timer), format!("timer {} is larger than {}", timer_as_usize, NEW_FISH_TIMER)))?;
+        *count += 1;
+    }
+    Ok(counts)
 }
 
-fn advance_timer_once(fish_state: &mut Vec<i64>) {
+fn advance_timer_once(fish_state: &mut [i64]) {
     let fish_giving_birth = fish_state[0];
-    for i in 0..NEW_FISH_TIMER {
-        fish_state[i] = fish_state[i + 1];
-    }
-    fish_state[NEW_FISH_TIMER] = fish_giving_birth;
+    fish_state.rotate_left(1);
     fish_state[RESET_FISH_TIMER] += fish_giving_birth;
 }
 
-fn advance_timer(fish_state: &mut Vec<i64>, days: i64) {
+fn advance_timer(fish_state: &mut [i64], days: i64) {
     for _ in 0..days {
         advance_timer_once(fish_state);
     }
 }
 
-pub fn count_lanternfish(input: &Vec<String>, days: i64) -> i64 {
-    let mut fish_state = parse_initial_state(input);
+pub fn count_lanternfish(initial_state: &[i64], days: i64) -> i64 {
+    let mut fish_state = initial_state.to_vec();
     advance_timer(&mut fish_state, days);
-    fish_state.iter().fold(0, |x, y| x + y)
+    fish_state.iter().sum()
+}
+
+/// Day 6: Lanternfish
+/// https://adventofcode.com/2021/day/6
+pub struct Day6;
+
+impl Solution for Day6 {
+    type Input = Vec<i64>;
+
+    fn parse(input: &PuzzleInput) -> anyhow::Result<Self::Input> {
+        parse_initial_state(input.lines())
+    }
+
+    fn part_1(initial_state: &Self::Input) -> anyhow::Result<Answer> {
+        Ok(count_lanternfish(initial_state, 80).into())
+    }
+
+    fn part_2(initial_state: &Self::Input) -> anyhow::Result<Answer> {
+        Ok(count_lanternfish(initial_state, 256).into())
+    }
+}
+
+register_day! {
+    day: 6,
+    title: "Lanternfish",
+    solution: Day6,
+    parts: [1, 2],
+    examples: [
+        Example { input: "3,4,3,1,2\n", part_1: Some("5934"), part_2: Some("26984457539") },
+    ],
 }
 
 
@@ -43,9 +80,17 @@ mod tests {
 
     #[test]
     fn test_simulate_lanternfish() {
-        let test_input = vec![TEST_INPUT.to_string()];
+        let test_input = parse_initial_state(&[TEST_INPUT.to_string()]).unwrap();
         assert_eq!(count_lanternfish(&test_input, 18), 26);
         assert_eq!(count_lanternfish(&test_input, 80), 5934);
         assert_eq!(count_lanternfish(&test_input, 256), 26984457539);
     }
+
+    #[test]
+    fn test_parse_errors() {
+        let parse = |s: &str| parse_initial_state(&[s.to_string()]).unwrap_err().to_string();
+        assert_eq!(parse("3,4,-3"), "line 1, column 5: could not parse '-3': invalid digit found in string");
+        assert_eq!(parse("3,4,9"), "line 1, column 5: timer 9 is larger than 8");
+        assert_eq!(parse(""), "The input is empty");
+    }
 }
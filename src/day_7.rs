@@ -1,26 +1,37 @@
-fn parse_crabs(input: &Vec<String>) -> Vec<i64> {
-    input[0].split(',').filter_map(|s| s.parse::<i64>().ok()).collect::<Vec<i64>>()
+use crate::input::PuzzleInput;
+use crate::registry::Example;
+use crate::solution::{Answer, Solution};
+use crate::utils::parse_at;
+
+
+/// Parses the comma separated horizontal positions of the crabs.
+pub fn parse_crabs(input: &[String]) -> anyhow::Result<Vec<i64>> {
+    let first_line = input.first().map(String::as_str).unwrap_or("");
+    if first_line.trim().is_empty() {
+        return Err(anyhow::anyhow!("The input is empty"));
+    }
+    first_line.trim().split(',').map(|s| parse_at(0, first_line, s)).collect()
 }
 
 /// This does not necessarily find the actual median, but just the crab which has index == crabs.len() / 2
 /// For odd lengths this is the actual median, but for even lengths this is the smaller of the two
 /// crabs which is in the middle. This is still sufficient for an optimal position, however.
-fn find_median(crabs: &mut Vec<i64>) -> i64 {
+fn find_median(crabs: &mut [i64]) -> i64 {
     crabs.sort();
     crabs[crabs.len() / 2]
 }
 
-fn calculate_fuel(crabs: &Vec<i64>, position: i64) -> i64 {
+fn calculate_fuel(crabs: &[i64], position: i64) -> i64 {
     crabs.iter().fold(0, |acc, crab| acc + (crab - position).abs())
 }
 
-pub fn find_optimum_constant(input: &Vec<String>) -> (i64, i64) {
-    let mut crabs = parse_crabs(input);
+pub fn find_optimum_constant(crabs: &[i64]) -> (i64, i64) {
+    let mut crabs = crabs.to_vec();
     let optimum = find_median(&mut crabs);
     (optimum, calculate_fuel(&crabs, optimum))
 }
 
-fn calculate_fuel_triangular(crabs: &Vec<i64>, position: i64) -> i64 {
+fn calculate_fuel_triangular(crabs: &[i64], position: i64) -> i64 {
     crabs.iter().fold(0, |acc, crab| {
         let diff = (crab - position).abs();
         acc + diff * (diff + 1) / 2
@@ -31,8 +42,8 @@ fn calculate_fuel_triangular(crabs: &Vec<i64>, position: i64) -> i64 {
 /// EDIT: it seems the arithmetic mean does work for most datasets here, because the triangular
 /// number creates an n^2 term. However, in some rare cases there is a small error that can
 /// potentially cause rounding errors.
-pub fn find_optimum_triangular(input: &Vec<String>) -> (i64, i64) {
-    let mut crabs = parse_crabs(input);
+pub fn find_optimum_triangular(crabs: &[i64]) -> (i64, i64) {
+    let mut crabs = crabs.to_vec();
     crabs.sort();
     let mut position = crabs[crabs.len() / 2];
     let mut cost = calculate_fuel_triangular(&crabs, position);
@@ -54,6 +65,38 @@ pub fn find_optimum_triangular(input: &Vec<String>) -> (i64, i64) {
     (position, cost)
 }
 
+/// Day 7: The Treachery of Whales
+/// https://adventofcode.com/2021/day/7
+pub struct Day7;
+
+impl Solution for Day7 {
+    type Input = Vec<i64>;
+
+    fn parse(input: &PuzzleInput) -> anyhow::Result<Self::Input> {
+        parse_crabs(input.lines())
+    }
+
+    fn part_1(crabs: &Self::Input) -> anyhow::Result<Answer> {
+        let (position, fuel) = find_optimum_constant(crabs);
+        Ok(Answer::from(fuel).with_details(vec![("position", position.into()), ("fuel", fuel.into())]))
+    }
+
+    fn part_2(crabs: &Self::Input) -> anyhow::Result<Answer> {
+        let (position, fuel) = find_optimum_triangular(crabs);
+        Ok(Answer::from(fuel).with_details(vec![("position", position.into()), ("fuel", fuel.into())]))
+    }
+}
+
+register_day! {
+    day: 7,
+    title: "The Treachery of Whales",
+    solution: Day7,
+    parts: [1, 2],
+    examples: [
+        Example { input: "16,1,2,0,4,2,7,1,2,14\n", part_1: Some("37"), part_2: Some("168") },
+    ],
+}
+
 
 #[cfg(test)]
 mod tests {
@@ -63,13 +106,19 @@ mod tests {
 
     #[test]
     fn test_find_optimum_constant() {
-        let test_input = vec![TEST_INPUT.to_string()];
+        let test_input = parse_crabs(&[TEST_INPUT.to_string()]).unwrap();
         assert_eq!(find_optimum_constant(&test_input), (2, 37));
     }
 
     #[test]
     fn test_find_optimum_triangular() {
-        let test_input = vec![TEST_INPUT.to_string()];
+        let test_input = parse_crabs(&[TEST_INPUT.to_string()]).unwrap();
         assert_eq!(find_optimum_triangular(&test_input), (5, 168));
     }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(parse_crabs(&["16,1,,0".to_string()]).unwrap_err().to_string(), "line 1, column 6: could not parse '': cannot parse integer from empty string");
+        assert!(parse_crabs(&[]).is_err());
+    }
 }
@@ -1,39 +1,57 @@
 use std::collections::{HashSet,HashMap};
 use num_traits::pow;
+use crate::input::PuzzleInput;
+use crate::registry::Example;
+use crate::solution::{Answer, Solution};
+use crate::utils::{column_of, error_at};
 
 
-/// Part 1 is fairly simple; we just go through the lines and count the number of segments
-pub fn part_1(lines: &Vec<String>) {
-    println!("1,4,7 and 8 appear {} times", count_unique_digits(lines));
+/// One line of the notes: the ten unique signal patterns, and the four digits of the output value.
+pub struct Entry {
+    signal_patterns: String,
+    output_value: String,
 }
 
-fn count_unique_digits(lines: &Vec<String>) -> i64 {
-    let mut count = 0;
-    let unique_segment_lens: HashSet<usize> = vec![2, 3, 4, 7].into_iter().collect(); // 1, 7, 4, and 8
-    for line in lines {
-        let parts = line.split(" | ").collect::<Vec<&str>>();
-        if parts.len() < 2 {
-            continue;
+/// Checks that `patterns`, found in `line` at line `line_index` of the input, is `count` words
+/// made of the segments a to g.
+fn check_patterns(line_index: usize, line: &str, patterns: &str, count: usize) -> anyhow::Result<()> {
+    for word in patterns.split_whitespace() {
+        if let Some((j, c)) = word.char_indices().find(|(_, c)| !('a'..='g').contains(c)) {
+            return Err(error_at(line_index, column_of(line, word) + j, format!("expected a segment from a to g, found '{}'", c)));
         }
-        count += parts[1].split_whitespace().filter(|digit_str| unique_segment_lens.contains(&digit_str.len())).count() as i64;
     }
-    count
+    let found = patterns.split_whitespace().count();
+    if found != count {
+        return Err(error_at(line_index, column_of(line, patterns), format!("expected {} patterns, found {}", count, found)));
+    }
+    Ok(())
 }
 
+/// Parses each line into its signal patterns and output value.
+pub fn parse_entries(lines: &[String]) -> anyhow::Result<Vec<Entry>> {
+    lines.iter().enumerate().filter(|(_, line)| !line.trim().is_empty()).map(|(i, line)| {
+        let (signal_patterns, output_value) = line.split_once(" | ")
+            .ok_or_else(|| error_at(i, 0, "expected the signal patterns and output value to be separated by ' | '"))?;
+        check_patterns(i, line, signal_patterns, 10)?;
+        check_patterns(i, line, output_value, 4)?;
+        Ok(Entry { signal_patterns: signal_patterns.to_string(), output_value: output_value.to_string() })
+    }).collect()
+}
 
-/// The strategy for part 2 is to first deduce a "digit map", being a map from the string of
-/// present segments in a given digit (this string must be sorted) to the digit those segments
-/// represent in that particular line, then to use this digit map to translate the four digits
-/// in the right hand side.
-pub fn part_2(lines: &Vec<String>) {
-    let sum = sum_output_values(lines);
-    println!("Sum of Output Values: {}", sum);
+/// Part 1 is fairly simple; we just go through the lines and count the number of segments
+pub fn count_unique_digits(entries: &[Entry]) -> i64 {
+    let mut count = 0;
+    let unique_segment_lens: HashSet<usize> = [2, 3, 4, 7].iter().copied().collect(); // 1, 7, 4, and 8
+    for entry in entries {
+        count += entry.output_value.split_whitespace().filter(|digit_str| unique_segment_lens.contains(&digit_str.len())).count() as i64;
+    }
+    count
 }
 
 /// Utility function for `deduce_digit_map`
 fn segment_diff_check(strings: Vec<String>, diff_string: String, target_length: i32) -> Option<(String, Vec<String>)> {
     let (s1_vec, others) = strings.into_iter().partition::<Vec<String>, _>(|s| {
-        s.chars().into_iter().filter(|c1| !diff_string.contains(|c2: char| *c1 == c2)).count() == target_length as usize
+        s.chars().filter(|c1| !diff_string.contains(*c1)).count() == target_length as usize
     });
     let s1 = s1_vec.into_iter().next()?;
     Some((s1, others))
@@ -47,11 +65,12 @@ fn segment_diff_check(strings: Vec<String>, diff_string: String, target_length:
 /// 3. "Subtract" segments from the digits in each unknown set with the digits we have already
 ///    found, in such a way that the remaining number of segments identifies one of the digits
 ///    in that set. Rinse and repeat.
+///
 /// For example, to distinguish 2, we exploit the fact that 2 is the only one out of 2, 3, and 5
 /// that has an e segment, which the pattern for 4 does not have. This means taking the set
 /// difference with 4 will leave the 2 with three segments, while 3 and 5 will have two, and we
 /// can simply partition based on whether there are three segments left or not.
-fn deduce_digit_map(signal_patterns_str: String) -> Option<HashMap<String, i32>> {
+pub fn deduce_digit_map(signal_patterns_str: &str) -> Option<HashMap<String, i32>> {
     let signal_patterns: Vec<String> = signal_patterns_str
         .split_whitespace()
         .map(str::to_string)
@@ -61,8 +80,8 @@ fn deduce_digit_map(signal_patterns_str: String) -> Option<HashMap<String, i32>>
     let pattern_4 = signal_patterns.iter().find(|pattern| pattern.len() == 4)?.to_string(); // contains the b, c, d, and f segments
     let pattern_7 = signal_patterns.iter().find(|pattern| pattern.len() == 3)?.to_string(); // contains the a, c, and f segments
     let pattern_8 = signal_patterns.iter().find(|pattern| pattern.len() == 7)?.to_string(); // contains the a, c, and f segments
-    let patterns_235: Vec<String> = signal_patterns.iter().filter(|pattern| pattern.len() == 5).map(|pattern| pattern.clone()).collect();
-    let patterns_069: Vec<String> = signal_patterns.iter().filter(|pattern| pattern.len() == 6).map(|pattern| pattern.clone()).collect();
+    let patterns_235: Vec<String> = signal_patterns.iter().filter(|pattern| pattern.len() == 5).cloned().collect();
+    let patterns_069: Vec<String> = signal_patterns.iter().filter(|pattern| pattern.len() == 6).cloned().collect();
 
     let (pattern_2, patterns_35) = segment_diff_check(patterns_235, pattern_4.clone(), 3)?;
     let (pattern_3, pattern_5_vec) = segment_diff_check(patterns_35, pattern_2.clone(), 1)?;
@@ -94,13 +113,14 @@ fn deduce_digit_map(signal_patterns_str: String) -> Option<HashMap<String, i32>>
     Some(patterns)
 }
 
-fn sum_output_values(lines: &Vec<String>) -> i64 {
-    lines.iter().filter_map(|line| {
-        let separator_index = line.find(" | ")?;
-        let (signal_pattern, output_value_digits) = line.split_at(separator_index);
-
-        let digit_map = deduce_digit_map(signal_pattern.to_string())?;
-        let output_value = output_value_digits
+/// The strategy for part 2 is to first deduce a "digit map", being a map from the string of
+/// present segments in a given digit (this string must be sorted) to the digit those segments
+/// represent in that particular line, then to use this digit map to translate the four digits
+/// in the right hand side.
+pub fn sum_output_values(entries: &[Entry]) -> i64 {
+    entries.iter().filter_map(|entry| {
+        let digit_map = deduce_digit_map(&entry.signal_patterns)?;
+        let output_value = entry.output_value
             .split_whitespace()
             .filter_map(|digit_str| {
                 let mut digit_str_sorted: Vec<char> = digit_str.chars().collect();
@@ -114,6 +134,50 @@ fn sum_output_values(lines: &Vec<String>) -> i64 {
     }).sum()
 }
 
+/// Day 8: Seven Segment Search
+/// https://adventofcode.com/2021/day/8
+pub struct Day8;
+
+impl Solution for Day8 {
+    type Input = Vec<Entry>;
+
+    fn parse(input: &PuzzleInput) -> anyhow::Result<Self::Input> {
+        parse_entries(input.lines())
+    }
+
+    fn part_1(entries: &Self::Input) -> anyhow::Result<Answer> {
+        Ok(count_unique_digits(entries).into())
+    }
+
+    fn part_2(entries: &Self::Input) -> anyhow::Result<Answer> {
+        Ok(sum_output_values(entries).into())
+    }
+}
+
+register_day! {
+    day: 8,
+    title: "Seven Segment Search",
+    solution: Day8,
+    parts: [1, 2],
+    examples: [
+        Example {
+            input: r#"be cfbegad cbdgef fgaecd cgeb fdcge agebfd fecdb fabcd edb | fdgacbe cefdb cefbgd gcbe
+edbfga begcd cbg gc gcadebf fbgde acbgfd abcde gfcbed gfec | fcgedb cgb dgebacf gc
+fgaebd cg bdaec gdafb agbcfd gdcbef bgcad gfac gcb cdgabef | cg cg fdcagb cbg
+fbegcd cbd adcefb dageb afcb bc aefdc ecdab fgdeca fcdbega | efabcd cedba gadfec cb
+aecbfdg fbg gf bafeg dbefa fcge gcbea fcaegb dgceab fcbdga | gecf egdcabf bgf bfgea
+fgeab ca afcebg bdacfeg cfaedg gcfdb baec bfadeg bafgc acf | gebdcfa ecba ca fadegcb
+dbcfg fgd bdegcaf fgec aegbdf ecdfab fbedc dacgb gdcebf gf | cefg dcbef fcge gbcadfe
+bdfegc cbegaf gecbf dfcage bdacg ed bedf ced adcbefg gebcd | ed bcgafe cdgba cbgef
+egadfb cdbfeg cegd fecab cgb gbdefca cg fgcdab egfdb bfceg | gbdfcae bgc cg cgb
+gcafb gcf dcaebfg ecagb gf abcdeg gaef cafbge fdbac fegbdc | fgae cfgab fg bagce
+"#,
+            part_1: Some("26"),
+            part_2: Some("61229"),
+        },
+    ],
+}
+
 
 #[cfg(test)]
 mod tests {
@@ -134,12 +198,20 @@ gcafb gcf dcaebfg ecagb gf abcdeg gaef cafbge fdbac fegbdc | fgae cfgab fg bagce
     #[test]
     fn test_count_unique_digits() {
         let test_input = TEST_INPUT.split('\n').map(str::to_string).collect::<Vec<String>>();
-        assert_eq!(count_unique_digits(&test_input), 26);
+        assert_eq!(count_unique_digits(&parse_entries(&test_input).unwrap()), 26);
     }
 
     #[test]
     fn test_sum_output_values() {
         let test_input = TEST_INPUT.split('\n').map(str::to_string).collect::<Vec<String>>();
-        assert_eq!(sum_output_values(&test_input), 61229);
+        assert_eq!(sum_output_values(&parse_entries(&test_input).unwrap()), 61229);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let parse = |s: &str| parse_entries(&[s.to_string()]).err().unwrap().to_string();
+        assert_eq!(parse("be cfbegad cbdgef fgaecd cgeb fdcge agebfd fecdb fabcd edb fdgacbe cefdb cefbgd gcbe"), "line 1, column 1: expected the signal patterns and output value to be separated by ' | '");
+        assert_eq!(parse("be cfbegad cbdgef fgaecd cgeb fdcge agebfd fecdb fabcd edb | fdgacbe cefdb cefbgd gcbx"), "line 1, column 86: expected a segment from a to g, found 'x'");
+        assert_eq!(parse("be cfbegad cbdgef fgaecd cgeb fdcge agebfd fecdb fabcd | fdgacbe cefdb cefbgd gcbe"), "line 1, column 1: expected 10 patterns, found 9");
     }
 }
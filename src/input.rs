@@ -0,0 +1,122 @@
+use std::fs;
+use std::io::{self, Read};
+use std::path::Path;
+
+
+/// The text of a puzzle input, read from a file, stdin or a string.
+///
+/// Windows line endings are converted to `\n`, and blank lines at the end are dropped, so an
+/// input gives the same lines whether or not it ends with a newline.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PuzzleInput {
+    text: String,
+    lines: Vec<String>,
+}
+
+/// A run of non-blank lines, separated from the rest of the input by blank lines.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Section<'a> {
+    /// The index of the section's first line in the whole input, for reporting errors.
+    pub first_line: usize,
+    pub lines: &'a [String],
+}
+
+impl PuzzleInput {
+    pub fn from_file(path: &Path) -> anyhow::Result<Self> {
+        let text = fs::read_to_string(path)
+            .map_err(|e| anyhow::anyhow!("Could not read input file {}: {}", path.display(), e))?;
+        Ok(Self::from(text.as_str()))
+    }
+
+    /// Reads stdin until EOF.
+    pub fn from_stdin() -> anyhow::Result<Self> {
+        let mut text = String::new();
+        io::stdin().lock().read_to_string(&mut text)
+            .map_err(|e| anyhow::anyhow!("Could not read input from stdin: {}", e))?;
+        Ok(Self::from(text.as_str()))
+    }
+
+    /// Reads the file at `path`, or stdin if no path is given.
+    pub fn read(path: Option<&Path>) -> anyhow::Result<Self> {
+        match path {
+            Some(path) => Self::from_file(path),
+            None => Self::from_stdin(),
+        }
+    }
+
+    /// The whole input, ending with a newline unless it is empty.
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    pub fn lines(&self) -> &[String] {
+        &self.lines
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.lines.is_empty()
+    }
+
+    /// Splits the input into its blank line separated sections, ignoring any extra blank lines.
+    pub fn sections(&self) -> Vec<Section<'_>> {
+        let mut sections = vec![];
+        let mut first_line = None;
+        for (i, line) in self.lines.iter().enumerate() {
+            match (first_line, line.trim().is_empty()) {
+                (None, false) => first_line = Some(i),
+                (Some(start), true) => {
+                    sections.push(Section { first_line: start, lines: &self.lines[start..i] });
+                    first_line = None;
+                },
+                _ => {},
+            }
+        }
+        if let Some(start) = first_line {
+            sections.push(Section { first_line: start, lines: &self.lines[start..] });
+        }
+        sections
+    }
+}
+
+impl From<&str> for PuzzleInput {
+    fn from(text: &str) -> Self {
+        let mut lines = text.split('\n')
+            .map(|line| line.strip_suffix('\r').unwrap_or(line).to_string())
+            .collect::<Vec<String>>();
+        while lines.last().is_some_and(|line| line.trim().is_empty()) {
+            lines.pop();
+        }
+        let text = lines.iter().map(|line| format!("{}\n", line)).collect();
+        PuzzleInput { text, lines }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use crate::input::*;
+
+    #[test]
+    fn test_line_endings() {
+        let unix = PuzzleInput::from("NNCB\n\nCH -> B\n");
+        assert_eq!(unix.lines(), ["NNCB", "", "CH -> B"]);
+        assert_eq!(unix.text(), "NNCB\n\nCH -> B\n");
+        assert_eq!(PuzzleInput::from("NNCB\r\n\r\nCH -> B\r\n"), unix);
+        assert_eq!(PuzzleInput::from("NNCB\n\nCH -> B"), unix);
+        assert_eq!(PuzzleInput::from("NNCB\n\nCH -> B\n\n\n"), unix);
+        assert!(PuzzleInput::from("").is_empty());
+        assert_eq!(PuzzleInput::from("\n").text(), "");
+    }
+
+    #[test]
+    fn test_sections() {
+        let input = PuzzleInput::from("\n6,10\n0,14\n\n\nfold along y=7\nfold along x=5\n");
+        let sections = input.sections();
+        assert_eq!(sections.len(), 2);
+        assert_eq!(sections[0].first_line, 1);
+        assert_eq!(sections[0].lines, ["6,10", "0,14"]);
+        assert_eq!(sections[1].first_line, 5);
+        assert_eq!(sections[1].lines, ["fold along y=7", "fold along x=5"]);
+        assert!(PuzzleInput::from("").sections().is_empty());
+    }
+}
@@ -0,0 +1,45 @@
+//! Solutions for Advent of Code 2021.
+//!
+//! Each day lives in its own module with a `DayN` type implementing [`solution::Solution`],
+//! which parses the puzzle input once and solves both parts from it. The building blocks each
+//! day is made of (parsers, simulations, search functions) are public as well, so they can be
+//! reused and tested on their own. Each module also registers its title, implemented parts and
+//! example inputs with `register_day!`, and [`days`] lists them all for the runner.
+//!
+//! ```
+//! use advent_of_code_2021::day_1::Day1;
+//! use advent_of_code_2021::input::PuzzleInput;
+//! use advent_of_code_2021::solution::{Answer, Solution};
+//!
+//! let input = PuzzleInput::from("199\n200\n208\n210\n200\n207\n240\n269\n260\n263\n");
+//! let depths = Day1::parse(&input).unwrap();
+//! assert_eq!(Day1::part_1(&depths).unwrap(), Answer::Int(7));
+//! assert_eq!(Day1::part_2(&depths).unwrap(), Answer::Int(5));
+//! ```
+
+pub mod utils;
+pub mod input;
+pub mod solution;
+pub mod answers;
+#[macro_use]
+pub mod registry;
+
+/// Declares each day's module, and `days` to list what they registered.
+macro_rules! days {
+    ($($module:ident),* $(,)?) => {
+        $(pub mod $module;)*
+
+        /// Every registered day, in order.
+        pub fn days() -> Vec<&'static registry::DayInfo> {
+            let mut days = vec![$(&$module::DAY),*];
+            days.sort_by_key(|info| info.day);
+            days
+        }
+    };
+}
+
+days! {
+    day_1, day_2, day_3, day_4, day_5, day_6, day_7, day_8, day_9, day_10,
+    day_11, day_12, day_13, day_14, day_15, day_16, day_17, day_18, day_19, day_20,
+    day_21, day_22, day_23, day_24, day_25,
+}
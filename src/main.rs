@@ -1,149 +1,296 @@
-mod utils;
-mod day_1;
-mod day_2;
-mod day_3;
-mod day_4;
-mod day_5;
-mod day_6;
-mod day_7;
-mod day_8;
-mod day_9;
-mod day_10;
-mod day_11;
-mod day_12;
-mod day_13;
-mod day_14;
-mod day_15;
-mod day_16;
-mod day_17;
-mod day_18;
-mod day_24;
-
-use std::io::{self, BufRead, Write};
-use std::collections::HashMap;
-use crate::utils::*;
-
-
-type AOCPartFn = fn(&Vec<String>) -> ();
-
-fn day_3_part_1(lines: &Vec<String>) {
-    let (gamma_rate_vec, epsilon_rate_vec) = day_3::get_gamma_and_epsilon_rates(&lines);
-    let gamma_rate_str = gamma_rate_vec.iter().collect::<String>();
-    let epsilon_rate_str = epsilon_rate_vec.iter().collect::<String>();
-    let gamma_rate = i64::from_str_radix(&gamma_rate_str, 2).unwrap();
-    let epsilon_rate = i64::from_str_radix(&epsilon_rate_str, 2).unwrap();
-    println!("Gamma Rate: {}, Epsilon Rate: {}, Multiplied: {}", gamma_rate_str, epsilon_rate_str, gamma_rate * epsilon_rate);
-}
-fn day_3_part_2(lines: &Vec<String>) {
-    let (o2gen_rate, co2scrubber_rate) = day_3::get_o2gen_and_co2scrubber_rates(&lines);
-    let o2gen_rate_i64 = i64::from_str_radix(&o2gen_rate, 2).unwrap();
-    let co2scrubber_rate_i64 = i64::from_str_radix(&co2scrubber_rate, 2).unwrap();
-    println!("O2 generator rate: {}, CO2 scrubber rate: {}, Multiplied: {}", o2gen_rate, co2scrubber_rate, o2gen_rate_i64 * co2scrubber_rate_i64);
-}
-
-fn day_4_part_1(lines: &Vec<String>) {
-    let mut s = lines.clone();
-    let (winning_number, winning_board) = day_4::find_winning_bingo_board(&mut s).unwrap();
-    let board_as_numbers = winning_board.map(|square| square.number);
-    println!("Winning Board:\n{:?}", board_as_numbers);
-    let score = day_4::get_score(winning_number, &winning_board);
-    println!("Winning Number: {}, Score: {}", winning_number, score);
-}
-fn day_4_part_2(lines: &Vec<String>) {
-    let mut s = lines.clone();
-    let (number, board) = day_4::find_last_to_win_bingo_board(&mut s).unwrap();
-    let board_as_numbers = board.map(|square| square.number);
-    println!("Last Board:\n{:?}", board_as_numbers);
-    let score = day_4::get_score(number, &board);
-    println!("Winning Number: {}, Score: {}", number, score);
-}
-
-fn day_5_part_1(lines: &Vec<String>) {
-    let count = day_5::count_overlapping_orthogonal_ventlines(&lines);
-    println!("Overlapping points: {}", count);
-}
-fn day_5_part_2(lines: &Vec<String>) {
-    let count = day_5::count_overlapping_ventlines(&lines);
-    println!("Overlapping points: {}", count);
-}
-
-fn day_6_part_1(lines: &Vec<String>) {
-    let count = day_6::count_lanternfish(&lines, 80);
-    println!("Number of Lanternfish: {}", count);
-}
-fn day_6_part_2(lines: &Vec<String>) {
-    let count = day_6::count_lanternfish(&lines, 256);
-    println!("Number of Lanternfish: {}", count);
-}
-
-fn day_7_part_1(lines: &Vec<String>) {
-    let (optimum, fuel) = day_7::find_optimum_constant(&lines);
-    println!("Optimal Position: {}, Total Fuel Cost: {}", optimum, fuel);
-}
-fn day_7_part_2(lines: &Vec<String>) {
-    let (optimum, fuel) = day_7::find_optimum_triangular(&lines);
-    println!("Optimal Position: {}, Total Fuel Cost: {}", optimum, fuel);
-}
-
-fn main() -> anyhow::Result<()> {
-    let mut map: HashMap<String, AOCPartFn> = HashMap::new();
-    map.insert("1:1".to_string(), day_1::part_1);
-    map.insert("1:2".to_string(), day_1::part_2);
-    map.insert("2:1".to_string(), day_2::part_1);
-    map.insert("2:2".to_string(), day_2::part_2);
-    map.insert("3:1".to_string(), day_3_part_1);
-    map.insert("3:2".to_string(), day_3_part_2);
-    map.insert("4:1".to_string(), day_4_part_1);
-    map.insert("4:2".to_string(), day_4_part_2);
-    map.insert("5:1".to_string(), day_5_part_1);
-    map.insert("5:2".to_string(), day_5_part_2);
-    map.insert("6:1".to_string(), day_6_part_1);
-    map.insert("6:2".to_string(), day_6_part_2);
-    map.insert("7:1".to_string(), day_7_part_1);
-    map.insert("7:2".to_string(), day_7_part_2);
-    map.insert("8:1".to_string(), day_8::part_1);
-    map.insert("8:2".to_string(), day_8::part_2);
-    map.insert("9:1".to_string(), day_9::part_1);
-    map.insert("9:2".to_string(), day_9::part_2);
-    map.insert("10:1".to_string(), day_10::part_1);
-    map.insert("10:2".to_string(), day_10::part_2);
-    map.insert("11:1".to_string(), day_11::part_1);
-    map.insert("11:2".to_string(), day_11::part_2);
-    map.insert("12:1".to_string(), day_12::part_1);
-    map.insert("12:2".to_string(), day_12::part_2);
-    map.insert("13:1".to_string(), day_13::part_1);
-    map.insert("13:2".to_string(), day_13::part_2);
-    map.insert("14:1".to_string(), day_14::part_1);
-    map.insert("14:2".to_string(), day_14::part_2);
-    map.insert("15:1".to_string(), day_15::part_1);
-    map.insert("15:2".to_string(), day_15::part_2);
-    map.insert("16:1".to_string(), day_16::part_1);
-    map.insert("16:2".to_string(), day_16::part_2);
-    map.insert("17:1".to_string(), day_17::part_1);
-    map.insert("17:2".to_string(), day_17::part_2);
-    map.insert("18:1".to_string(), day_18::part_1);
-    map.insert("18:2".to_string(), day_18::part_2);
-    map.insert("24:1".to_string(), day_24::part_1);
-    map.insert("24:2".to_string(), day_24::part_2);
-
-    let stdin = io::stdin();
-    let stdout = io::stdout();
-
-    print!("Choose Day: ");
-    stdout.lock().flush().unwrap();
-    let mut key = String::new();
-    let _ = stdin.lock().read_line(&mut key)?;
-    key.remove(key.len() - 1);
-    key.push(':');
-    print!("Choose Part: ");
-    stdout.lock().flush().unwrap();
-    let _ = stdin.lock().read_line(&mut key)?;
-    key.remove(key.len() - 1);
-
-    let func_option = map.get(&key);
-    if let Some(func) = func_option {
-        let lines = parse_lines(&stdin)?;
-        func(&lines);
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::process;
+use std::time::Duration;
+use clap::{ArgEnum, Parser, Subcommand};
+use advent_of_code_2021::*;
+use advent_of_code_2021::answers::{Answers, Verdict};
+use advent_of_code_2021::solution::{duration_to_ms, Answer, DayResult};
+use advent_of_code_2021::input::PuzzleInput;
+use advent_of_code_2021::registry::DayInfo;
+
+
+type Registry = BTreeMap<u32, &'static DayInfo>;
+
+#[derive(Parser)]
+#[clap(about = "Solutions for Advent of Code 2021")]
+struct Cli {
+    #[clap(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Run a day's solution against a puzzle input, or every day's with --all
+    Run {
+        /// Day of the puzzle (1-25)
+        #[clap(short, long, required_unless_present = "all", conflicts_with = "all")]
+        day: Option<u32>,
+        /// Part of the puzzle (1 or 2); every implemented part is run if omitted
+        #[clap(short, long, requires = "day")]
+        part: Option<u32>,
+        /// File to read the puzzle input from (reads stdin until EOF if omitted)
+        #[clap(short, long, requires = "day")]
+        input: Option<PathBuf>,
+        /// Run the implemented parts of every day, reading each input from the inputs directory
+        #[clap(short, long)]
+        all: bool,
+        /// Directory containing the inputs for --all, named day_1.txt, day_2.txt, ...
+        #[clap(long, default_value = "inputs")]
+        inputs_dir: PathBuf,
+        /// How to print the results
+        #[clap(long, arg_enum, default_value = "text")]
+        format: Format,
+    },
+    /// Check the answers for the real puzzle inputs against the recorded answers
+    Verify {
+        /// Only verify this day
+        #[clap(short, long)]
+        day: Option<u32>,
+        /// TOML file of known-good answers, with a table per day and a key per part
+        #[clap(long, default_value = "answers.toml")]
+        answers: PathBuf,
+        /// Directory containing the inputs, named day_1.txt, day_2.txt, ...
+        #[clap(long, default_value = "inputs")]
+        inputs_dir: PathBuf,
+    },
+    /// List the registered days, with their titles, implemented parts and examples
+    List,
+}
+
+#[derive(ArgEnum, Clone, Copy, PartialEq)]
+enum Format {
+    /// A table for reading in the terminal
+    Text,
+    /// A JSON document with the answers, their details, timings and errors
+    Json,
+}
+
+fn registry() -> Registry {
+    days().into_iter().map(|info| (info.day, info)).collect()
+}
+
+/// Runs `parts` of `day` on `input`, saying which day's input could not be parsed if that fails.
+fn solve(info: &DayInfo, input: &PuzzleInput, parts: &[u32]) -> anyhow::Result<DayResult> {
+    (info.run)(input, parts).map_err(|e| anyhow::anyhow!("Could not parse the input for day {}: {}", info.day, e))
+}
+
+/// Fails if any part of `result` failed, so that the runner exits with an error after printing it.
+fn check_parts(day: u32, result: &DayResult) -> anyhow::Result<()> {
+    match result.parts.iter().find(|part| part.answer.is_err()) {
+        Some(part) => Err(anyhow::anyhow!("Day {} part {} failed", day, part.part)),
+        None => Ok(()),
+    }
+}
+
+/// Where `run --all` expects the puzzle input for `day` to be.
+fn input_path(inputs_dir: &Path, day: u32) -> PathBuf {
+    inputs_dir.join(format!("day_{}.txt", day))
+}
+
+fn format_duration(duration: Duration) -> String {
+    format!("{:.3} ms", duration.as_secs_f64() * 1000.0)
+}
+
+fn format_answer(answer: &anyhow::Result<Answer>) -> String {
+    match answer.as_ref().map(Answer::value) {
+        Ok(Answer::Art(lines)) => format!("({} lines of art)", lines.len()),
+        Ok(answer) => answer.to_string(),
+        Err(e) => format!("error: {}", e),
+    }
+}
+
+fn print_table_header() {
+    println!("{:>3}  {:>4}  {:<24}  {:>12}  {:>12}", "Day", "Part", "Answer", "Parse", "Solve");
+}
+
+/// Prints one row per part, followed by any art answers in full. The parse time is only shown
+/// on the first row, as it is shared between both parts.
+fn print_table_rows(day: u32, result: &DayResult) {
+    for (i, part) in result.parts.iter().enumerate() {
+        let parse_time = if i == 0 { format_duration(result.parse_time) } else { String::new() };
+        println!("{:>3}  {:>4}  {:<24}  {:>12}  {:>12}", day, part.part, format_answer(&part.answer), parse_time, format_duration(part.solve_time));
+        if let Ok(Answer::Art(lines)) = part.answer.as_ref().map(Answer::value) {
+            for line in lines {
+                println!("           {}", line);
+            }
+        }
+    }
+}
+
+fn run_day(map: &Registry, day: u32, part: Option<u32>, input: Option<PathBuf>, format: Format) -> anyhow::Result<()> {
+    let info = map.get(&day).ok_or_else(|| anyhow::anyhow!("No solution registered for day {}", day))?;
+    if let Some(part) = part {
+        if !info.has_part(part) {
+            return Err(anyhow::anyhow!("Day {} has no part {} implemented", day, part));
+        }
+    }
+    let input = PuzzleInput::read(input.as_deref())?;
+    let parts = part.map_or(info.parts.to_vec(), |part| vec![part]);
+
+    if format == Format::Json {
+        let result = solve(info, &input, &parts);
+        println!("{:#}", day_to_json(day, &result));
+        return check_parts(day, &result?);
+    }
+
+    if let Some(part) = part {
+        let result = solve(info, &input, &[part])?;
+        let answer = result.parts.into_iter().next().map(|part| part.answer)
+            .unwrap_or_else(|| Err(anyhow::anyhow!("Day {} did not run part {}", day, part)))?;
+        println!("{}", answer);
+    } else {
+        let result = solve(info, &input, &parts)?;
+        print_table_header();
+        print_table_rows(day, &result);
+        check_parts(day, &result)?;
+    }
+    Ok(())
+}
+
+/// Reports a day's results as JSON, or the error if its input could not be read or parsed.
+fn day_to_json(day: u32, result: &anyhow::Result<DayResult>) -> serde_json::Value {
+    match result {
+        Ok(result) => result.to_json(day),
+        Err(e) => serde_json::json!({ "day": day, "parse_ms": null, "parts": [], "error": e.to_string() }),
+    }
+}
+
+fn run_all(map: &Registry, inputs_dir: &Path, format: Format) -> anyhow::Result<()> {
+    let mut total_parse = Duration::ZERO;
+    let mut total_solve = Duration::ZERO;
+    let mut days_json = vec![];
+    let mut failures = 0;
+
+    if format == Format::Text {
+        print_table_header();
+    }
+    for (day, info) in map {
+        let path = input_path(inputs_dir, *day);
+        if !path.exists() {
+            match format {
+                Format::Text => println!("{:>3}  {:>4}  no input at {}", day, "", path.display()),
+                Format::Json => days_json.push(day_to_json(*day, &Err(anyhow::anyhow!("No input at {}", path.display())))),
+            }
+            continue;
+        }
+        let result = PuzzleInput::from_file(&path).and_then(|input| solve(info, &input, info.parts));
+        match &result {
+            Ok(result) => {
+                total_parse += result.parse_time;
+                total_solve += result.total_time() - result.parse_time;
+                failures += result.parts.iter().filter(|part| part.answer.is_err()).count();
+            },
+            Err(_) => failures += 1,
+        }
+        match (format, result) {
+            (Format::Text, Ok(result)) => print_table_rows(*day, &result),
+            (Format::Text, Err(e)) => println!("{:>3}  {:>4}  error: {}", day, "", e),
+            (Format::Json, result) => days_json.push(day_to_json(*day, &result)),
+        }
+    }
+
+    match format {
+        Format::Text => {
+            println!("{:<35}  {:>12}  {:>12}", "Total", format_duration(total_parse), format_duration(total_solve));
+            println!("{:<35}  {:>26}", "Grand Total", format_duration(total_parse + total_solve));
+        },
+        Format::Json => println!("{:#}", serde_json::json!({
+            "days": days_json,
+            "total_parse_ms": duration_to_ms(total_parse),
+            "total_solve_ms": duration_to_ms(total_solve),
+            "total_ms": duration_to_ms(total_parse + total_solve),
+        })),
+    }
+    if failures > 0 {
+        return Err(anyhow::anyhow!("{} days or parts failed", failures));
     }
     Ok(())
 }
+
+fn verify(map: &Registry, day: Option<u32>, answers_path: &Path, inputs_dir: &Path) -> anyhow::Result<()> {
+    let answers = Answers::load(answers_path)?;
+    if let Some(day) = day {
+        if !map.contains_key(&day) {
+            return Err(anyhow::anyhow!("No solution registered for day {}", day));
+        }
+    }
+
+    let (mut passed, mut failed, mut missing, mut errors, mut no_input) = (0, 0, 0, 0, 0);
+    for (day, info) in map.iter().filter(|(d, _)| day.is_none_or(|day| day == **d)) {
+        let path = input_path(inputs_dir, *day);
+        if !path.exists() {
+            println!("Day {:>2}: NO INPUT at {}", day, path.display());
+            no_input += 1;
+            continue;
+        }
+        let result = match PuzzleInput::from_file(&path).and_then(|input| solve(info, &input, info.parts)) {
+            Ok(result) => result,
+            Err(e) => {
+                println!("Day {:>2}: ERROR {}", day, e);
+                errors += 1;
+                continue;
+            },
+        };
+        for part in result.parts.iter() {
+            let label = format!("Day {:>2} Part {}", day, part.part);
+            match answers::check(answers.get(*day, part.part), &part.answer) {
+                Verdict::Pass => {
+                    println!("{}: PASS", label);
+                    passed += 1;
+                },
+                Verdict::Fail { expected, actual } => {
+                    println!("{}: FAIL", label);
+                    for line in answers::diff(&expected, &actual) {
+                        println!("    {}", line);
+                    }
+                    failed += 1;
+                },
+                Verdict::Missing { actual } => {
+                    println!("{}: MISSING (no recorded answer, got {})", label, actual.lines().next().unwrap_or(""));
+                    missing += 1;
+                },
+                Verdict::Error(e) => {
+                    println!("{}: ERROR {}", label, e);
+                    errors += 1;
+                },
+            }
+        }
+    }
+
+    println!("{} passed, {} failed, {} missing, {} errors, {} days without input", passed, failed, missing, errors, no_input);
+    if failed > 0 || errors > 0 {
+        return Err(anyhow::anyhow!("{} answers did not match and {} could not be checked", failed, errors));
+    }
+    Ok(())
+}
+
+fn list(map: &Registry) {
+    println!("{:>3}  {:<24}  {:<5}  {:>8}  Puzzle", "Day", "Title", "Parts", "Examples");
+    for info in map.values() {
+        let parts = info.parts.iter().map(u32::to_string).collect::<Vec<String>>().join(",");
+        println!("{:>3}  {:<24}  {:<5}  {:>8}  {}", info.day, info.title, parts, info.examples.len(), info.url);
+    }
+}
+
+fn run(cli: Cli) -> anyhow::Result<()> {
+    let map = registry();
+
+    match cli.command {
+        Command::Run { all: true, inputs_dir, format, .. } => run_all(&map, &inputs_dir, format),
+        Command::Run { day: Some(day), part, input, format, .. } => run_day(&map, day, part, input, format),
+        Command::Run { .. } => Err(anyhow::anyhow!("Either --day or --all must be given")),
+        Command::Verify { day, answers, inputs_dir } => verify(&map, day, &answers, &inputs_dir),
+        Command::List => {
+            list(&map);
+            Ok(())
+        },
+    }
+}
+
+fn main() {
+    if let Err(e) = run(Cli::parse()) {
+        eprintln!("error: {}", e);
+        process::exit(1);
+    }
+}
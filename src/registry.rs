@@ -0,0 +1,64 @@
+use crate::input::PuzzleInput;
+use crate::solution::DayResult;
+
+
+/// An example input from a puzzle's description, with the answers it is given for each part.
+/// An answer is `None` where the puzzle gives no answer for that part.
+#[derive(Debug, Clone, Copy)]
+pub struct Example {
+    pub input: &'static str,
+    pub part_1: Option<&'static str>,
+    pub part_2: Option<&'static str>,
+}
+
+/// What a day's module registers about itself, see `register_day!`.
+#[derive(Debug, Clone, Copy)]
+pub struct DayInfo {
+    pub day: u32,
+    pub title: &'static str,
+    pub url: &'static str,
+    /// The parts that are implemented.
+    pub parts: &'static [u32],
+    pub examples: &'static [Example],
+    /// Parses the input and runs the given parts, as `Solution::run`.
+    pub run: fn(&PuzzleInput, &[u32]) -> anyhow::Result<DayResult>,
+}
+
+impl DayInfo {
+    pub fn has_part(&self, part: u32) -> bool {
+        self.parts.contains(&part)
+    }
+}
+
+/// Registers a day's solution as `DAY`, which `crate::days` collects for the runner.
+///
+/// ```ignore
+/// register_day! {
+///     day: 6,
+///     title: "Lanternfish",
+///     solution: Day6,
+///     parts: [1, 2],
+///     examples: [
+///         Example { input: "3,4,3,1,2\n", part_1: Some("5934"), part_2: Some("26984457539") },
+///     ],
+/// }
+/// ```
+macro_rules! register_day {
+    (
+        day: $day:literal,
+        title: $title:literal,
+        solution: $solution:ty,
+        parts: [$($part:literal),* $(,)?],
+        examples: [$($example:expr),* $(,)?] $(,)?
+    ) => {
+        /// This day's registration, see `crate::registry::DayInfo`.
+        pub const DAY: $crate::registry::DayInfo = $crate::registry::DayInfo {
+            day: $day,
+            title: $title,
+            url: concat!("https://adventofcode.com/2021/day/", $day),
+            parts: &[$($part),*],
+            examples: &[$($example),*],
+            run: <$solution as $crate::solution::Solution>::run,
+        };
+    };
+}
@@ -0,0 +1,260 @@
+use std::convert::TryFrom;
+use std::fmt;
+use std::time::{Duration, Instant};
+use crate::input::PuzzleInput;
+
+
+/// The answer to one part of a puzzle.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Answer {
+    Int(i64),
+    Text(String),
+    /// Multi-line output that has to be read by eye, such as the folded paper in Day 13.
+    Art(Vec<String>),
+    /// An answer along with named values found on the way to it, such as the position the crabs
+    /// line up at in Day 7. These are reported alongside the answer but are not part of it.
+    Detailed { answer: Box<Answer>, details: Vec<(String, Answer)> },
+}
+
+impl Answer {
+    pub fn with_details(self, details: Vec<(&str, Answer)>) -> Self {
+        let details = details.into_iter().map(|(name, value)| (name.to_string(), value)).collect();
+        Answer::Detailed { answer: Box::new(self.value().clone()), details }
+    }
+
+    /// The answer itself, without any details.
+    pub fn value(&self) -> &Answer {
+        match self {
+            Answer::Detailed { answer, .. } => answer.value(),
+            answer => answer,
+        }
+    }
+
+    pub fn details(&self) -> &[(String, Answer)] {
+        match self {
+            Answer::Detailed { details, .. } => details,
+            _ => &[],
+        }
+    }
+
+    /// Integers become JSON numbers, text becomes a string and art becomes an array of strings,
+    /// one per line. Details are left out, see `details_to_json`.
+    pub fn to_json(&self) -> serde_json::Value {
+        match self.value() {
+            Answer::Int(value) => serde_json::json!(value),
+            Answer::Text(text) => serde_json::json!(text),
+            Answer::Art(lines) => serde_json::json!(lines),
+            Answer::Detailed { .. } => unreachable!("value() never returns a detailed answer"),
+        }
+    }
+
+    /// The details as a JSON object keyed by name.
+    pub fn details_to_json(&self) -> serde_json::Value {
+        self.details().iter()
+            .map(|(name, value)| (name.clone(), value.to_json()))
+            .collect::<serde_json::Map<String, serde_json::Value>>()
+            .into()
+    }
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Answer::Int(value) => write!(f, "{}", value),
+            Answer::Text(text) => write!(f, "{}", text),
+            Answer::Art(lines) => write!(f, "{}", lines.join("\n")),
+            Answer::Detailed { answer, .. } => write!(f, "{}", answer),
+        }
+    }
+}
+
+impl From<i64> for Answer {
+    fn from(value: i64) -> Self {
+        Answer::Int(value)
+    }
+}
+
+impl From<u64> for Answer {
+    fn from(value: u64) -> Self {
+        match i64::try_from(value) {
+            Ok(value) => Answer::Int(value),
+            Err(_) => Answer::Text(value.to_string()),
+        }
+    }
+}
+
+impl From<u128> for Answer {
+    fn from(value: u128) -> Self {
+        match i64::try_from(value) {
+            Ok(value) => Answer::Int(value),
+            Err(_) => Answer::Text(value.to_string()),
+        }
+    }
+}
+
+impl From<usize> for Answer {
+    fn from(value: usize) -> Self {
+        Answer::from(value as u64)
+    }
+}
+
+impl From<char> for Answer {
+    fn from(c: char) -> Self {
+        Answer::Text(c.to_string())
+    }
+}
+
+impl From<String> for Answer {
+    fn from(text: String) -> Self {
+        Answer::Text(text)
+    }
+}
+
+impl From<&str> for Answer {
+    fn from(text: &str) -> Self {
+        Answer::Text(text.to_string())
+    }
+}
+
+
+/// The answer to one part along with how long it took to solve, not including parsing.
+pub struct PartResult {
+    pub part: u32,
+    pub answer: anyhow::Result<Answer>,
+    pub solve_time: Duration,
+}
+
+/// The results of parsing an input once and solving some of the parts from it.
+pub struct DayResult {
+    pub parse_time: Duration,
+    pub parts: Vec<PartResult>,
+}
+
+impl PartResult {
+    /// Reports the answer and its details, or the error if the part failed, with the solve time
+    /// in milliseconds.
+    pub fn to_json(&self) -> serde_json::Value {
+        let (answer, details, error) = match &self.answer {
+            Ok(answer) => (answer.to_json(), answer.details_to_json(), serde_json::Value::Null),
+            Err(e) => (serde_json::Value::Null, serde_json::json!({}), serde_json::json!(e.to_string())),
+        };
+        serde_json::json!({
+            "part": self.part,
+            "answer": answer,
+            "details": details,
+            "error": error,
+            "solve_ms": duration_to_ms(self.solve_time),
+        })
+    }
+}
+
+impl DayResult {
+    pub fn total_time(&self) -> Duration {
+        self.parts.iter().fold(self.parse_time, |acc, part| acc + part.solve_time)
+    }
+
+    pub fn to_json(&self, day: u32) -> serde_json::Value {
+        serde_json::json!({
+            "day": day,
+            "parse_ms": duration_to_ms(self.parse_time),
+            "parts": self.parts.iter().map(PartResult::to_json).collect::<Vec<serde_json::Value>>(),
+            "error": serde_json::Value::Null,
+        })
+    }
+}
+
+pub fn duration_to_ms(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1000.0
+}
+
+
+/// A day's puzzle. The input is parsed once into `Input`, and both parts are solved from it.
+pub trait Solution {
+    type Input;
+
+    fn parse(input: &PuzzleInput) -> anyhow::Result<Self::Input>;
+    fn part_1(input: &Self::Input) -> anyhow::Result<Answer>;
+    fn part_2(input: &Self::Input) -> anyhow::Result<Answer>;
+
+    /// Parses `input` and solves each of `parts` from it, timing each step separately.
+    /// Only a failure to parse is returned as an error; a part that fails has its error
+    /// recorded in its `PartResult` instead.
+    fn run(input: &PuzzleInput, parts: &[u32]) -> anyhow::Result<DayResult> where Self: Sized {
+        let parse_start = Instant::now();
+        let input = Self::parse(input)?;
+        let parse_time = parse_start.elapsed();
+
+        let parts = parts.iter().map(|part| {
+            let solve_start = Instant::now();
+            let answer = match part {
+                1 => Self::part_1(&input),
+                2 => Self::part_2(&input),
+                _ => Err(anyhow::anyhow!("There is no part {}", part)),
+            };
+            PartResult { part: *part, answer, solve_time: solve_start.elapsed() }
+        }).collect();
+
+        Ok(DayResult { parse_time, parts })
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use crate::solution::*;
+
+    struct Sum;
+
+    impl Solution for Sum {
+        type Input = Vec<i64>;
+
+        fn parse(input: &PuzzleInput) -> anyhow::Result<Self::Input> {
+            Ok(input.lines().iter().map(|line| line.parse::<i64>()).collect::<Result<Vec<i64>, _>>()?)
+        }
+
+        fn part_1(numbers: &Self::Input) -> anyhow::Result<Answer> {
+            Ok(numbers.iter().sum::<i64>().into())
+        }
+
+        fn part_2(_: &Self::Input) -> anyhow::Result<Answer> {
+            Err(anyhow::anyhow!("not implemented"))
+        }
+    }
+
+    #[test]
+    fn test_answer_display() {
+        assert_eq!(Answer::from(150i64).to_string(), "150");
+        assert_eq!(Answer::from(u64::MAX).to_string(), "18446744073709551615");
+        assert_eq!(Answer::from("NNCB").to_string(), "NNCB");
+        assert_eq!(Answer::Art(vec!["#.#".to_string(), ".#.".to_string()]).to_string(), "#.#\n.#.");
+        assert_eq!(Answer::from(37i64).with_details(vec![("position", 2i64.into())]).to_string(), "37");
+    }
+
+    #[test]
+    fn test_answer_details() {
+        let answer = Answer::from(1588i64).with_details(vec![("most_common_element", 'B'.into()), ("most_common_count", 1749i64.into())]);
+        assert_eq!(answer.value(), &Answer::Int(1588));
+        assert_eq!(answer.details()[0], ("most_common_element".to_string(), Answer::Text("B".to_string())));
+        assert_eq!(answer.to_json(), serde_json::json!(1588));
+        assert_eq!(answer.details_to_json(), serde_json::json!({"most_common_element": "B", "most_common_count": 1749}));
+        assert_eq!(Answer::Int(3).details_to_json(), serde_json::json!({}));
+    }
+
+    #[test]
+    fn test_part_result_to_json() {
+        let part = PartResult { part: 2, answer: Err(anyhow::anyhow!("failed")), solve_time: Duration::from_millis(3) };
+        assert_eq!(part.to_json(), serde_json::json!({"part": 2, "answer": null, "details": {}, "error": "failed", "solve_ms": 3.0}));
+        let part = PartResult { part: 1, answer: Ok(Answer::Art(vec!["#.".to_string()])), solve_time: Duration::ZERO };
+        assert_eq!(part.to_json()["answer"], serde_json::json!(["#."]));
+    }
+
+    #[test]
+    fn test_run() {
+        let result = Sum::run(&PuzzleInput::from("1\n2\n"), &[1, 2, 3]).unwrap();
+        assert_eq!(result.parts.iter().map(|part| part.part).collect::<Vec<u32>>(), [1, 2, 3]);
+        assert_eq!(result.parts[0].answer.as_ref().unwrap(), &Answer::Int(3));
+        assert!(result.parts[1].answer.is_err());
+        assert!(result.parts[2].answer.is_err());
+        assert!(Sum::run(&PuzzleInput::from("x"), &[1]).is_err());
+    }
+}
@@ -1,22 +1,51 @@
-use std::io::{Stdin, BufRead};
+use std::fmt::Display;
+use std::str::FromStr;
+
+mod grid;
+pub use grid::Grid;
 
 /// Takes a string of whitespace separated integers and returns those integers in a Vec
-pub fn parse_ints_to_vec(lines: &Vec<String>) -> Vec<u64> {
-    return lines.iter().filter_map(|word| word.parse::<u64>().ok()).collect::<Vec<u64>>()
+pub fn parse_ints_to_vec(lines: &[String]) -> anyhow::Result<Vec<u64>> {
+    lines.iter().enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| parse_at(i, line, line.trim()))
+        .collect()
 }
 
-pub fn parse_lines(stdin: &Stdin) -> anyhow::Result<Vec<String>> {
-    println!("Enter Input (separate numbers with whitespace, end sequence by entering the character '+' or an EOF character (CTRL-D on Unix, CTRL-Z on Windows):");
+/// Creates an error for a problem found at `column` of line `line` of the input. Both are
+/// indexes counting from zero, but are reported counting from one as an editor would show them.
+pub fn error_at(line: usize, column: usize, message: impl Display) -> anyhow::Error {
+    anyhow::anyhow!("line {}, column {}: {}", line + 1, column + 1, message)
+}
 
-    let mut buffer = vec![];
-    stdin.lock().read_until(b'+', &mut buffer)?;
-    if buffer[buffer.len() - 1] == b'+' {
-        buffer.pop();
-    }
-    let s = String::from_utf8(buffer)?;
-    Ok(s.split('\n').map(str::to_string).collect::<Vec<String>>())
+/// Finds the column `piece` starts at, where `piece` is a slice of `line` such as one returned
+/// by `split` or `trim`.
+pub fn column_of(line: &str, piece: &str) -> usize {
+    piece.as_ptr() as usize - line.as_ptr() as usize
+}
+
+/// Parses `piece`, a slice of line `line_index` of the input, reporting where it is if it can't be parsed.
+pub fn parse_at<T>(line_index: usize, line: &str, piece: &str) -> anyhow::Result<T> where T: FromStr, T::Err: Display {
+    piece.parse::<T>().map_err(|e| error_at(line_index, column_of(line, piece), format!("could not parse '{}': {}", piece, e)))
+}
+
+/// Parses a digit found at `column` of line `line` of the input.
+pub fn digit_at(line: usize, column: usize, c: char) -> anyhow::Result<u32> {
+    c.to_digit(10).ok_or_else(|| error_at(line, column, format!("expected a digit, found '{}'", c)))
 }
 
+/// Parses a range such as `x=20..30`, where `axis` is the name it should start with.
+pub fn parse_range(line_index: usize, line: &str, range: &str, axis: &str) -> anyhow::Result<(i64, i64)> {
+    let bounds = range.strip_prefix(axis).and_then(|rest| rest.strip_prefix('='))
+        .ok_or_else(|| error_at(line_index, column_of(line, range), format!("expected the range to start with '{}='", axis)))?;
+    let (min, max) = bounds.split_once("..")
+        .ok_or_else(|| error_at(line_index, column_of(line, bounds), "expected the bounds to be separated by '..'"))?;
+    let (min, max) = (parse_at::<i64>(line_index, line, min)?, parse_at::<i64>(line_index, line, max)?);
+    if min > max {
+        return Err(error_at(line_index, column_of(line, bounds), format!("the {} range is empty", axis)));
+    }
+    Ok((min, max))
+}
 
 #[cfg(test)]
 mod tests {
@@ -26,11 +55,26 @@ mod tests {
     fn test_parse() {
         let empty = vec!["".to_string()];
         let one = vec!["0".to_string()];
-        let two = vec!["0", "1"].iter().map(|s| s.to_string()).collect::<Vec<String>>();
-        let three = vec!["199", "200", "208"].iter().map(|s| s.to_string()).collect::<Vec<String>>();
-        assert_eq!(parse_ints_to_vec(&empty), []);
-        assert_eq!(parse_ints_to_vec(&one), [0]);
-        assert_eq!(parse_ints_to_vec(&two), [0, 1]);
-        assert_eq!(parse_ints_to_vec(&three), [199, 200, 208]);
+        let two = ["0", "1"].iter().map(|s| s.to_string()).collect::<Vec<String>>();
+        let three = ["199", "200", "208"].iter().map(|s| s.to_string()).collect::<Vec<String>>();
+        assert_eq!(parse_ints_to_vec(&empty).unwrap(), Vec::<u64>::new());
+        assert_eq!(parse_ints_to_vec(&one).unwrap(), [0]);
+        assert_eq!(parse_ints_to_vec(&two).unwrap(), [0, 1]);
+        assert_eq!(parse_ints_to_vec(&three).unwrap(), [199, 200, 208]);
+        let bad = ["199", "2o0"].iter().map(|s| s.to_string()).collect::<Vec<String>>();
+        assert_eq!(parse_ints_to_vec(&bad).unwrap_err().to_string(), "line 2, column 1: could not parse '2o0': invalid digit found in string");
+    }
+
+    #[test]
+    fn test_error_context() {
+        let line = "3,4,x,1";
+        let piece = line.split(',').nth(2).unwrap();
+        assert_eq!(column_of(line, piece), 4);
+        assert_eq!(parse_at::<i64>(0, line, piece).unwrap_err().to_string(), "line 1, column 5: could not parse 'x': invalid digit found in string");
+        assert_eq!(digit_at(2, 3, '7').unwrap(), 7);
+        assert_eq!(digit_at(2, 3, '.').unwrap_err().to_string(), "line 3, column 4: expected a digit, found '.'");
+        let line = "x=-5..5,y=3..1";
+        assert_eq!(parse_range(0, line, &line[..7], "x").unwrap(), (-5, 5));
+        assert_eq!(parse_range(0, line, &line[8..], "y").unwrap_err().to_string(), "line 1, column 11: the y range is empty");
     }
 }
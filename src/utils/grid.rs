@@ -0,0 +1,233 @@
+use std::fmt;
+use std::ops::{Index, IndexMut};
+use crate::utils::{column_of, digit_at, error_at};
+
+
+/// A rectangle of cells, indexed by `(row, column)` from the top left.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Grid<T> {
+    height: usize,
+    width: usize,
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    /// Builds a grid by calling `f` with the position of every cell.
+    pub fn from_fn(height: usize, width: usize, mut f: impl FnMut((usize, usize)) -> T) -> Self {
+        let cells = (0..height).flat_map(|i| (0..width).map(move |j| (i, j))).map(&mut f).collect();
+        Grid { height, width, cells }
+    }
+
+    /// Builds a grid from its rows, which must all be the same length.
+    pub fn from_rows(rows: Vec<Vec<T>>) -> anyhow::Result<Self> {
+        let height = rows.len();
+        let width = rows.first().map_or(0, Vec::len);
+        let mut cells = Vec::with_capacity(height * width);
+        for (i, row) in rows.into_iter().enumerate() {
+            if row.len() != width {
+                return Err(anyhow::anyhow!("Row {} has {} cells, but the first row has {}", i, row.len(), width));
+            }
+            cells.extend(row);
+        }
+        Ok(Grid { height, width, cells })
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    pub fn contains(&self, (i, j): (usize, usize)) -> bool {
+        i < self.height && j < self.width
+    }
+
+    pub fn get(&self, position: (usize, usize)) -> Option<&T> {
+        if self.contains(position) { self.cells.get(position.0 * self.width + position.1) } else { None }
+    }
+
+    pub fn get_mut(&mut self, position: (usize, usize)) -> Option<&mut T> {
+        if self.contains(position) { self.cells.get_mut(position.0 * self.width + position.1) } else { None }
+    }
+
+    /// Every position in the grid, row by row.
+    pub fn positions(&self) -> impl Iterator<Item = (usize, usize)> {
+        let width = self.width;
+        (0..self.height).flat_map(move |i| (0..width).map(move |j| (i, j)))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        self.cells.iter()
+    }
+
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut T> {
+        self.cells.iter_mut()
+    }
+
+    /// Every cell along with its position, row by row.
+    pub fn indexed_iter(&self) -> impl Iterator<Item = ((usize, usize), &T)> {
+        self.positions().zip(self.cells.iter())
+    }
+
+    pub fn row(&self, i: usize) -> &[T] {
+        &self.cells[i * self.width..(i + 1) * self.width]
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        // chunks panics on a chunk size of 0, which a grid with no columns would otherwise give
+        self.cells.chunks(self.width.max(1))
+    }
+
+    pub fn column(&self, j: usize) -> impl Iterator<Item = &T> {
+        self.cells.iter().skip(j).step_by(self.width.max(1)).take(self.height)
+    }
+
+    pub fn columns(&self) -> impl Iterator<Item = impl Iterator<Item = &T>> {
+        (0..self.width).map(move |j| self.column(j))
+    }
+
+    /// The positions directly above, below, left and right of `position` that are inside the grid.
+    pub fn neighbours_4(&self, position: (usize, usize)) -> impl Iterator<Item = (usize, usize)> {
+        self.offsets(position, &[(-1, 0), (0, -1), (0, 1), (1, 0)])
+    }
+
+    /// The positions around `position`, including diagonally, that are inside the grid.
+    pub fn neighbours_8(&self, position: (usize, usize)) -> impl Iterator<Item = (usize, usize)> {
+        self.offsets(position, &[(-1, -1), (-1, 0), (-1, 1), (0, -1), (0, 1), (1, -1), (1, 0), (1, 1)])
+    }
+
+    fn offsets(&self, (i, j): (usize, usize), offsets: &'static [(isize, isize)]) -> impl Iterator<Item = (usize, usize)> {
+        let (height, width) = (self.height, self.width);
+        offsets.iter().filter_map(move |(di, dj)| {
+            let neighbour = (i.checked_add_signed(*di)?, j.checked_add_signed(*dj)?);
+            if neighbour.0 < height && neighbour.1 < width { Some(neighbour) } else { None }
+        })
+    }
+
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid { height: self.height, width: self.width, cells: self.cells.iter().map(f).collect() }
+    }
+
+    /// Repeats the grid `down` times downwards and `across` times across, with `f` giving each
+    /// cell's value from the original cell and the `(row, column)` of the tile it is in.
+    pub fn tile(&self, down: usize, across: usize, f: impl Fn(&T, (usize, usize)) -> T) -> Grid<T> {
+        Grid::from_fn(self.height * down, self.width * across, |(i, j)| {
+            f(&self[(i % self.height, j % self.width)], (i / self.height, j / self.width))
+        })
+    }
+}
+
+impl<T: From<u8>> Grid<T> {
+    /// Parses a rectangle of single digits, one row per non-empty line.
+    pub fn parse_digits(lines: &[String]) -> anyhow::Result<Self> {
+        let mut rows: Vec<Vec<T>> = vec![];
+        for (i, line) in lines.iter().enumerate() {
+            let trimmed = line.trim();
+            if trimmed.is_empty() {
+                continue;
+            }
+            let start = column_of(line, trimmed);
+            let row = trimmed.char_indices()
+                .map(|(j, c)| digit_at(i, start + j, c).map(|digit| T::from(digit as u8)))
+                .collect::<anyhow::Result<Vec<T>>>()?;
+            if let Some(first) = rows.first() {
+                if row.len() != first.len() {
+                    return Err(error_at(i, start, format!("expected {} digits like the first row, found {}", first.len(), row.len())));
+                }
+            }
+            rows.push(row);
+        }
+        Grid::from_rows(rows)
+    }
+}
+
+impl<T> Index<(usize, usize)> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, position: (usize, usize)) -> &T {
+        self.get(position).unwrap_or_else(|| panic!("{:?} is outside of a {}x{} grid", position, self.height, self.width))
+    }
+}
+
+impl<T> IndexMut<(usize, usize)> for Grid<T> {
+    fn index_mut(&mut self, position: (usize, usize)) -> &mut T {
+        let (height, width) = (self.height, self.width);
+        self.get_mut(position).unwrap_or_else(|| panic!("{:?} is outside of a {}x{} grid", position, height, width))
+    }
+}
+
+/// Shows each row on its own line, with the cells next to each other.
+impl<T: fmt::Display> fmt::Display for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (i, row) in self.rows().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            row.iter().try_for_each(|cell| write!(f, "{}", cell))?;
+        }
+        Ok(())
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use crate::utils::grid::*;
+
+    fn get_test_grid() -> Grid<u8> {
+        Grid::parse_digits(&["123".to_string(), "456".to_string(), "".to_string()]).unwrap()
+    }
+
+    #[test]
+    fn test_parse_digits() {
+        let grid = get_test_grid();
+        assert_eq!((grid.height(), grid.width()), (2, 3));
+        assert_eq!(grid[(1, 0)], 4);
+        let parse = |s: &str| Grid::<u8>::parse_digits(&s.split('\n').map(str::to_string).collect::<Vec<String>>());
+        assert_eq!(parse("123\n4x6").unwrap_err().to_string(), "line 2, column 2: expected a digit, found 'x'");
+        assert_eq!(parse("123\n45").unwrap_err().to_string(), "line 2, column 1: expected 3 digits like the first row, found 2");
+        assert!(parse("").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_indexing() {
+        let mut grid = get_test_grid();
+        assert_eq!(grid.get((1, 2)), Some(&6));
+        assert_eq!(grid.get((2, 0)), None);
+        assert_eq!(grid.get((0, 3)), None);
+        grid[(0, 1)] = 9;
+        assert_eq!(grid.row(0), [1, 9, 3]);
+    }
+
+    #[test]
+    fn test_neighbours() {
+        let grid = get_test_grid();
+        assert_eq!(grid.neighbours_4((0, 0)).collect::<Vec<_>>(), [(0, 1), (1, 0)]);
+        assert_eq!(grid.neighbours_4((1, 1)).collect::<Vec<_>>(), [(0, 1), (1, 0), (1, 2)]);
+        assert_eq!(grid.neighbours_8((0, 2)).collect::<Vec<_>>(), [(0, 1), (1, 1), (1, 2)]);
+        assert_eq!(grid.neighbours_8((1, 1)).count(), 5);
+    }
+
+    #[test]
+    fn test_rows_and_columns() {
+        let grid = get_test_grid();
+        assert_eq!(grid.rows().collect::<Vec<&[u8]>>(), [[1, 2, 3], [4, 5, 6]]);
+        assert_eq!(grid.column(1).copied().collect::<Vec<u8>>(), [2, 5]);
+        assert_eq!(grid.columns().map(|column| column.sum::<u8>()).collect::<Vec<u8>>(), [5, 7, 9]);
+        assert_eq!(grid.indexed_iter().nth(4), Some(((1, 1), &5)));
+    }
+
+    #[test]
+    fn test_tile_and_display() {
+        let grid = get_test_grid();
+        let tiled = grid.tile(2, 2, |cell, (tile_i, tile_j)| cell + (tile_i + tile_j) as u8);
+        assert_eq!(tiled.to_string(), "123234\n456567\n234345\n567678");
+        assert_eq!(grid.map(|cell| if cell % 2 == 0 { '#' } else { '.' }).to_string(), ".#.\n#.#");
+    }
+}
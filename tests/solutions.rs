@@ -0,0 +1,105 @@
+use advent_of_code_2021::input::PuzzleInput;
+use advent_of_code_2021::solution::{Answer, Solution};
+use advent_of_code_2021::{days, day_1, day_2, day_6, day_7, day_13, day_15, day_16, day_18};
+
+
+fn solve<S: Solution>(s: &str) -> (Answer, Answer) {
+    let input = S::parse(&PuzzleInput::from(s)).unwrap();
+    (S::part_1(&input).unwrap().value().clone(), S::part_2(&input).unwrap().value().clone())
+}
+
+#[test]
+fn test_day_1() {
+    let input = "199\n200\n208\n210\n200\n207\n240\n269\n260\n263\n";
+    assert_eq!(solve::<day_1::Day1>(input), (Answer::Int(7), Answer::Int(5)));
+}
+
+#[test]
+fn test_day_2() {
+    let input = "forward 5\ndown 5\nforward 8\nup 3\ndown 8\nforward 2\n";
+    assert_eq!(solve::<day_2::Day2>(input), (Answer::Int(150), Answer::Int(900)));
+}
+
+#[test]
+fn test_day_6() {
+    assert_eq!(solve::<day_6::Day6>("3,4,3,1,2\n"), (Answer::Int(5934), Answer::Int(26984457539)));
+}
+
+#[test]
+fn test_day_7() {
+    let input = "16,1,2,0,4,2,7,1,2,14\n";
+    assert_eq!(solve::<day_7::Day7>(input), (Answer::Int(37), Answer::Int(168)));
+    let crabs = day_7::parse_crabs(PuzzleInput::from(input).lines()).unwrap();
+    assert_eq!(day_7::find_optimum_constant(&crabs), (2, 37));
+    assert_eq!(day_7::find_optimum_triangular(&crabs), (5, 168));
+    let answer = day_7::Day7::part_2(&crabs).unwrap();
+    assert_eq!(answer.details_to_json(), serde_json::json!({"position": 5, "fuel": 168}));
+}
+
+#[test]
+fn test_day_13() {
+    let input = "0,0\n4,0\n0,4\n4,4\n\nfold along x=2\n";
+    let (part_1, part_2) = solve::<day_13::Day13>(input);
+    assert_eq!(part_1, Answer::Int(2));
+    assert_eq!(part_2, Answer::Art(vec!["#".to_string(), ".".to_string(), ".".to_string(), ".".to_string(), "#".to_string()]));
+}
+
+#[test]
+fn test_day_15_lowest_total_risk() {
+    let input = r#"1163751742
+1381373672
+2136511328
+3694931569
+7463417111
+1319128137
+1359912421
+3125421639
+1293138521
+2311944581
+"#;
+    let risk_levels = day_15::parse_risk_levels(PuzzleInput::from(input).lines()).unwrap();
+    let (graph, start, end) = day_15::parse_graph(&risk_levels, false);
+    assert_eq!(day_15::lowest_total_risk(&graph, start, end), Some(40));
+    let (graph, start, end) = day_15::parse_graph(&risk_levels, true);
+    assert_eq!(day_15::lowest_total_risk(&graph, start, end), Some(315));
+}
+
+#[test]
+fn test_day_16_parse_packet() {
+    let packets = day_16::parse_transmission(PuzzleInput::from("D2FE28").lines()).unwrap();
+    assert_eq!(packets.len(), 1);
+    assert_eq!(day_16::sum_version_numbers(&packets), 6);
+    assert_eq!(day_16::calc_packets(&packets).unwrap(), 2021);
+    assert_eq!(solve::<day_16::Day16>("9C0141080250320F1802104A08"), (Answer::Int(20), Answer::Int(1)));
+}
+
+#[test]
+fn test_day_18_reduce() {
+    let number = day_18::parse("[[[[[4,3],4],4],[7,[[8,4],9]]],[1,1]]").unwrap();
+    assert_eq!(day_18::reduce(&number), day_18::parse("[[[[0,7],4],[[7,8],[6,0]]],[8,1]]").unwrap());
+    let sum = day_18::add(&day_18::parse("[[[[4,3],4],4],[7,[[8,4],9]]]").unwrap(), &day_18::parse("[1,1]").unwrap());
+    assert_eq!(day_18::magnitude(&sum), 1384);
+}
+
+#[test]
+fn test_registered_examples() {
+    for info in days() {
+        for (i, example) in info.examples.iter().enumerate() {
+            let expected = [(1, example.part_1), (2, example.part_2)];
+            let parts = expected.iter().filter(|(_, answer)| answer.is_some()).map(|(part, _)| *part).collect::<Vec<u32>>();
+            let result = (info.run)(&PuzzleInput::from(example.input), &parts).unwrap();
+            for (part, (_, answer)) in result.parts.iter().zip(expected.iter().filter(|(_, answer)| answer.is_some())) {
+                let actual = part.answer.as_ref().map(Answer::to_string).unwrap();
+                assert_eq!(Some(actual.as_str()), *answer, "day {} example {} part {}", info.day, i + 1, part.part);
+            }
+        }
+    }
+}
+
+#[test]
+fn test_registry() {
+    let days = days();
+    assert!(days.windows(2).all(|pair| pair[0].day < pair[1].day));
+    assert!(days.iter().all(|info| info.url.ends_with(&format!("/day/{}", info.day))));
+    assert!(days.iter().all(|info| info.examples.is_empty() || !info.parts.is_empty()));
+}